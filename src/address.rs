@@ -0,0 +1,33 @@
+/// Represents the I2C address of the INA3221, selected by the pin the A0 pin is tied to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Address {
+    /// A0 tied to GND (0x40)
+    #[default]
+    Gnd = 0x40,
+    /// A0 tied to VS (0x41)
+    Vs = 0x41,
+    /// A0 tied to SDA (0x42)
+    Sda = 0x42,
+    /// A0 tied to SCL (0x43)
+    Scl = 0x43,
+}
+
+impl Address {
+    /// All four addresses in order, useful for scanning the bus
+    pub const ALL: [Address; 4] = [Address::Gnd, Address::Vs, Address::Sda, Address::Scl];
+
+    /// Gets the 7-bit I2C address
+    pub fn bits(&self) -> u8 {
+        *self as u8
+    }
+
+    pub(crate) fn index(&self) -> usize {
+        (self.bits() - Address::Gnd.bits()) as usize
+    }
+}
+
+impl From<Address> for u8 {
+    fn from(address: Address) -> Self {
+        address.bits()
+    }
+}
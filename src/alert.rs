@@ -0,0 +1,193 @@
+use crate::{Channel, MaskEnableFlags};
+
+/// Represents one of the four open-drain alert outputs of the INA3221
+///
+/// All four lines are active-low, so they are pulled low when the alert fires
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AlertLine {
+    /// Critical pin, driven by the critical alert limits and the summation alert limit
+    Critical,
+    /// Warning pin, driven by the warning alert limits
+    Warning,
+    /// PV pin, driven by the power valid limits of the bus voltages
+    PowerValid,
+    /// TC pin, driven by the timing control of the channel 1 bus voltage at power-up
+    TimingControl,
+}
+
+impl AlertLine {
+    /// Gets the Mask/Enable flags that drive the alert line
+    pub fn flags(&self) -> MaskEnableFlags {
+        match self {
+            AlertLine::Critical => {
+                MaskEnableFlags::CRITICAL_ALERT_1
+                    | MaskEnableFlags::CRITICAL_ALERT_2
+                    | MaskEnableFlags::CRITICAL_ALERT_3
+                    | MaskEnableFlags::SUMMATION_ALERT
+            }
+            AlertLine::Warning => {
+                MaskEnableFlags::WARNING_ALERT_1
+                    | MaskEnableFlags::WARNING_ALERT_2
+                    | MaskEnableFlags::WARNING_ALERT_3
+            }
+            AlertLine::PowerValid => MaskEnableFlags::POWER_VALID_ALERT,
+            AlertLine::TimingControl => MaskEnableFlags::TIMING_CONTROL_ALERT,
+        }
+    }
+
+    fn channel_flag(&self, channel: Channel) -> Option<MaskEnableFlags> {
+        match self {
+            AlertLine::Critical => Some(channel.critical_alert_flag()),
+            AlertLine::Warning => Some(channel.warning_alert_flag()),
+            AlertLine::PowerValid | AlertLine::TimingControl => None,
+        }
+    }
+}
+
+/// Represents an alert that fired on one of the alert lines
+///
+/// The flags are decoded from the Mask/Enable register read after the line was asserted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlertEvent {
+    line: AlertLine,
+    flags: MaskEnableFlags,
+}
+
+impl AlertEvent {
+    /// Creates an alert event from the flags read after an alert line was asserted
+    ///
+    /// This is useful for decoding the result of `read_alert_flags()` when polling the alert
+    /// lines with the blocking driver
+    pub fn new(line: AlertLine, flags: MaskEnableFlags) -> AlertEvent {
+        AlertEvent { line, flags }
+    }
+
+    /// Gets the alert line that was asserted
+    pub fn line(&self) -> AlertLine {
+        self.line
+    }
+
+    /// Gets all flags read from the Mask/Enable register, including those of other lines
+    pub fn flags(&self) -> MaskEnableFlags {
+        self.flags
+    }
+
+    /// Gets the status of all alerts read from the Mask/Enable register
+    pub fn status(&self) -> AlertStatus {
+        AlertStatus::from(self.flags)
+    }
+
+    /// Iterates over the channels that exceeded their limit for the asserted line
+    ///
+    /// The power valid and timing control lines are not specific to a channel,
+    /// so no channels are returned for them
+    pub fn channels(&self) -> impl Iterator<Item = Channel> {
+        let AlertEvent { line, flags } = *self;
+        Channel::ALL.into_iter().filter(move |channel| {
+            line.channel_flag(*channel)
+                .is_some_and(|flag| flags.contains(flag))
+        })
+    }
+
+    /// Checks if the summed shunt voltage exceeded the summation alert limit
+    ///
+    /// This only applies to the critical line, which is also driven by the summation alert
+    pub fn is_summation(&self) -> bool {
+        self.line == AlertLine::Critical && self.flags.contains(MaskEnableFlags::SUMMATION_ALERT)
+    }
+}
+
+/// Represents the kind of limit exceeded by a monitoring channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AlertKind {
+    /// The shunt voltage exceeded the critical alert limit
+    Critical,
+    /// The shunt voltage exceeded the warning alert limit
+    Warning,
+}
+
+impl AlertKind {
+    /// All alert kinds in order of severity
+    pub const ALL: [AlertKind; 2] = [AlertKind::Critical, AlertKind::Warning];
+
+    /// Gets the Mask/Enable status flag of the alert kind for a specific channel
+    pub fn flag(&self, channel: Channel) -> MaskEnableFlags {
+        match self {
+            AlertKind::Critical => channel.critical_alert_flag(),
+            AlertKind::Warning => channel.warning_alert_flag(),
+        }
+    }
+}
+
+/// Represents the status bits of the Mask/Enable register
+///
+/// Unlike [`MaskEnableFlags`], this only reports the alert and conversion status,
+/// so the latch and summation control bits are not mixed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlertStatus {
+    flags: MaskEnableFlags,
+}
+
+impl AlertStatus {
+    /// Checks if a monitoring channel exceeded its critical alert limit
+    pub fn is_critical(&self, channel: Channel) -> bool {
+        self.is_alert(channel, AlertKind::Critical)
+    }
+
+    /// Checks if a monitoring channel exceeded its warning alert limit
+    pub fn is_warning(&self, channel: Channel) -> bool {
+        self.is_alert(channel, AlertKind::Warning)
+    }
+
+    /// Checks if a monitoring channel exceeded a specific kind of alert limit
+    pub fn is_alert(&self, channel: Channel, kind: AlertKind) -> bool {
+        self.flags.contains(kind.flag(channel))
+    }
+
+    /// Checks if the summed shunt voltage exceeded the summation alert limit
+    pub fn is_summation(&self) -> bool {
+        self.flags.contains(MaskEnableFlags::SUMMATION_ALERT)
+    }
+
+    /// Checks if the power valid alert flag is set
+    pub fn is_power_valid(&self) -> bool {
+        self.flags.contains(MaskEnableFlags::POWER_VALID_ALERT)
+    }
+
+    /// Checks if the timing control alert flag is set
+    pub fn is_timing_control(&self) -> bool {
+        self.flags.contains(MaskEnableFlags::TIMING_CONTROL_ALERT)
+    }
+
+    /// Checks if the last conversion has completed
+    pub fn is_conversion_ready(&self) -> bool {
+        self.flags.contains(MaskEnableFlags::CONVERSION_READY)
+    }
+
+    /// Checks if any channel exceeded a critical or warning alert limit, or the summation limit
+    pub fn has_alerts(&self) -> bool {
+        self.flags.intersects(MaskEnableFlags::ALERT_FLAGS)
+    }
+
+    /// Iterates over the critical and warning alerts raised by each monitoring channel
+    pub fn iter(&self) -> impl Iterator<Item = (Channel, AlertKind)> {
+        let status = *self;
+        Channel::ALL
+            .into_iter()
+            .flat_map(|channel| AlertKind::ALL.into_iter().map(move |kind| (channel, kind)))
+            .filter(move |(channel, kind)| status.is_alert(*channel, *kind))
+    }
+
+    /// Gets the raw status flags
+    pub fn flags(&self) -> MaskEnableFlags {
+        self.flags
+    }
+}
+
+impl From<MaskEnableFlags> for AlertStatus {
+    fn from(flags: MaskEnableFlags) -> Self {
+        AlertStatus {
+            flags: flags & MaskEnableFlags::STATUS_FLAGS,
+        }
+    }
+}
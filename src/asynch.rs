@@ -0,0 +1,869 @@
+//! Async driver for the INA3221 built on [embedded-hal-async]
+//!
+//! Requires the `async` feature to be enabled.
+//!
+//! [embedded-hal-async]: https://docs.rs/embedded-hal-async/
+
+use crate::common::{
+    self, AlertShadow, BurstRead, ConversionTimer, RegisterCache, Shunts, SnapshotBuilder,
+};
+use crate::config::{AVERAGING_SHIFT, BUS_CONVERSION_TIME_SHIFT, SHUNT_CONVERSION_TIME_SHIFT};
+use crate::registers::Register;
+use crate::{
+    Address, AlertEvent, AlertLine, AlertStatus, Averaging, Channel, Configuration, ConversionTime,
+    Error, MaskEnableFlags, OperatingMode, Power, ScanResult, Snapshot,
+};
+use embedded_hal_async::delay::DelayUs;
+use embedded_hal_async::digital::Wait;
+use embedded_hal_async::i2c::I2c;
+use ohms::{Current, Resistance, Voltage};
+
+/// Scans all four INA3221 addresses on an I2C bus for genuine INA3221s
+///
+/// This is the async equivalent of [`scan`](crate::scan)
+pub async fn scan<I2C, E>(i2c: &mut I2C) -> Result<ScanResult, Error<E>>
+where
+    I2C: I2c<Error = E>,
+    E: embedded_hal_async::i2c::Error,
+{
+    let mut result = ScanResult::default();
+
+    for address in Address::ALL {
+        let probe = INA3221::probe(&mut *i2c, address).await.map(|_| ());
+        if common::is_device_found(probe)? {
+            result.insert(address);
+        }
+    }
+
+    Ok(result)
+}
+
+/// Async device driver for the INA3221 current and power monitor
+///
+/// This has the same API as the blocking [`INA3221`](crate::INA3221) driver, except that every
+/// method that accesses the I2C bus is an `async fn` and takes `&mut self`, so it can be used
+/// with async executors such as [Embassy] without stalling other tasks.
+///
+/// See the blocking driver for details on the configuration, channels, shunt resistors,
+/// current and power calculation, operating modes, alerts, and summation.
+///
+/// # Example
+///
+/// ```rust
+/// # use embedded_hal_async::{delay::DelayUs, i2c::I2c};
+/// # use ina3221::asynch::INA3221;
+/// # use ina3221::prelude::*;
+/// # async fn example<I: I2c, D: DelayUs>(i2c: I, mut delay: D) {
+/// let mut ina = INA3221::new(i2c, Address::Gnd);
+/// ina.set_shunt_resistance(Channel::Ch1, 100u32.milli_ohms());
+///
+/// let current = ina.get_current(Channel::Ch1).await.unwrap();
+/// let snapshot = ina.measure_once(&mut delay).await.unwrap();
+/// # }
+/// ```
+///
+/// # Alerts
+///
+/// The four alert pins can be awaited using any [`Wait`] input pin, after which the Mask/Enable
+/// register is read and decoded into an [`AlertEvent`] describing which alerts were raised.
+/// Only the alerts of the awaited line are acknowledged, so the others are still returned by
+/// `read_alert_flags()`.
+///
+/// By default the wait is for the next falling edge of the pin, so an alert that is already
+/// asserted is not reported until the pin is released and asserted again. With
+/// `set_alert_level_checked(true)`, the wait instead returns straight away if the pin is already
+/// low. Only enable this when the alerts are latched: a transparent alert stays asserted for as
+/// long as the limit is exceeded, so every wait would return at once without yielding.
+///
+/// ```rust
+/// # use embedded_hal_async::{digital::Wait, i2c::I2c};
+/// # use ina3221::asynch::INA3221;
+/// # async fn example<I: I2c, P: Wait>(mut ina: INA3221<I>, mut critical_pin: P) {
+/// let event = ina.wait_for_critical_alert(&mut critical_pin).await.unwrap();
+/// for channel in event.channels() {
+///     // Channel exceeded its critical alert limit
+/// }
+/// # }
+/// ```
+///
+/// [Embassy]: https://embassy.dev
+///
+pub struct INA3221<I2C> {
+    i2c: I2C,
+    address: Address,
+    shunts: Shunts,
+    alerts: AlertShadow,
+    cache: RegisterCache,
+    burst: bool,
+    alert_level_checked: bool,
+}
+
+impl<I2C, E> INA3221<I2C>
+where
+    I2C: I2c<Error = E>,
+{
+    /// Create a new INA3221 driver instance from an I2C peripheral on a specific address
+    ///
+    /// The address is selected by tying the A0 pin to GND, VS, SDA, or SCL
+    pub fn new(i2c: I2C, address: Address) -> INA3221<I2C> {
+        INA3221 {
+            i2c,
+            address,
+            shunts: Shunts::default(),
+            alerts: AlertShadow::default(),
+            cache: RegisterCache::default(),
+            burst: false,
+            alert_level_checked: false,
+        }
+    }
+
+    /// Create a new INA3221 driver instance, verifying that the device is a genuine INA3221
+    ///
+    /// Returns `Error::UnexpectedDevice` if the manufacturer ID is not 0x5449 or the die ID is not
+    /// 0x3220, such as when an INA219 answers at the same address.
+    ///
+    /// The I2C peripheral is dropped if the device cannot be verified, so pass `&mut I2C` instead
+    /// to keep using the bus afterwards
+    pub async fn probe(i2c: I2C, address: Address) -> Result<INA3221<I2C>, Error<E>> {
+        let mut ina = INA3221::new(i2c, address);
+        ina.verify_identity().await?;
+        Ok(ina)
+    }
+
+    /// Create a new INA3221 driver instance like `probe()`, then reset the device
+    ///
+    /// This guarantees that the device starts from the power-on default configuration
+    /// regardless of how it was left by previous firmware
+    pub async fn probe_and_reset(i2c: I2C, address: Address) -> Result<INA3221<I2C>, Error<E>> {
+        let mut ina = INA3221::probe(i2c, address).await?;
+        ina.reset().await?;
+        Ok(ina)
+    }
+
+    /// Destroys the driver instance, returning the I2C peripheral
+    pub fn destroy(self) -> I2C {
+        self.i2c
+    }
+
+    /// Gets the I2C address of the INA3221
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Gets the active configuration bits from the INA3221
+    pub async fn get_configuration(&mut self) -> Result<u16, Error<E>> {
+        self.read_register(Register::Configuration).await
+    }
+
+    /// Reads the Configuration register from the INA3221
+    pub async fn read_config(&mut self) -> Result<Configuration, Error<E>> {
+        let config = self.get_configuration().await?;
+        Ok(Configuration::from_bits(config))
+    }
+
+    /// Writes the Configuration register of the INA3221 in a single transaction
+    ///
+    /// This is the exact inverse of `read_config()`
+    pub async fn write_config(&mut self, config: &Configuration) -> Result<(), Error<E>> {
+        self.write_register(Register::Configuration, config.bits())
+            .await
+    }
+
+    /// Gets the operating mode of the INA3221
+    pub async fn get_mode(&mut self) -> Result<OperatingMode, Error<E>> {
+        let config = self.get_configuration().await?;
+        Ok(OperatingMode::from_bits(config))
+    }
+
+    /// Sets the operating mode of the INA3221
+    ///
+    /// Setting the mode to `OperatingMode::Triggered` will trigger a measurement cycle
+    pub async fn set_mode(&mut self, mode: OperatingMode) -> Result<(), Error<E>> {
+        let config = self.get_configuration().await?;
+        let new_config = common::update_mode(config, mode);
+        self.write_register(Register::Configuration, new_config)
+            .await
+    }
+
+    /// Gets the number of samples averaged together for each measurement
+    pub async fn get_averaging(&mut self) -> Result<Averaging, Error<E>> {
+        let config = self.get_configuration().await?;
+        Ok(Averaging::from_bits(config >> AVERAGING_SHIFT))
+    }
+
+    /// Sets the number of samples averaged together for each measurement
+    ///
+    /// Averaging applies to both the shunt and bus voltage measurements of all channels
+    pub async fn set_averaging(&mut self, averaging: Averaging) -> Result<(), Error<E>> {
+        self.set_config_field(AVERAGING_SHIFT, averaging as u16)
+            .await
+    }
+
+    /// Gets the conversion time used for bus voltage measurements
+    pub async fn get_bus_conversion_time(&mut self) -> Result<ConversionTime, Error<E>> {
+        let config = self.get_configuration().await?;
+        Ok(ConversionTime::from_bits(
+            config >> BUS_CONVERSION_TIME_SHIFT,
+        ))
+    }
+
+    /// Sets the conversion time used for bus voltage measurements
+    pub async fn set_bus_conversion_time(
+        &mut self,
+        conversion_time: ConversionTime,
+    ) -> Result<(), Error<E>> {
+        self.set_config_field(BUS_CONVERSION_TIME_SHIFT, conversion_time as u16)
+            .await
+    }
+
+    /// Gets the conversion time used for shunt voltage measurements
+    pub async fn get_shunt_conversion_time(&mut self) -> Result<ConversionTime, Error<E>> {
+        let config = self.get_configuration().await?;
+        Ok(ConversionTime::from_bits(
+            config >> SHUNT_CONVERSION_TIME_SHIFT,
+        ))
+    }
+
+    /// Sets the conversion time used for shunt voltage measurements
+    pub async fn set_shunt_conversion_time(
+        &mut self,
+        conversion_time: ConversionTime,
+    ) -> Result<(), Error<E>> {
+        self.set_config_field(SHUNT_CONVERSION_TIME_SHIFT, conversion_time as u16)
+            .await
+    }
+
+    /// Gets the enabled status for all three channels, storing them in an array
+    ///
+    /// Returns `Error::InvalidChannel` if `statuses` has fewer than three elements
+    pub async fn get_channels_enabled(&mut self, statuses: &mut [bool]) -> Result<(), Error<E>> {
+        let config = self.get_configuration().await?;
+        common::decode_channels_enabled(config, statuses)
+    }
+
+    /// Sets the enabled status for all three channels
+    ///
+    /// Returns `Error::InvalidChannel` if `enabled` has fewer than three elements
+    pub async fn set_channels_enabled(&mut self, enabled: &[bool]) -> Result<(), Error<E>> {
+        let config = self.get_configuration().await?;
+        let new_config = common::update_channels_enabled(config, enabled)?;
+        self.write_register(Register::Configuration, new_config)
+            .await
+    }
+
+    /// Checks if a monitoring channel is enabled on the INA3221
+    pub async fn is_channel_enabled(&mut self, channel: Channel) -> Result<bool, Error<E>> {
+        let flag = channel.enable_flag();
+        let config = self.get_configuration().await?;
+        Ok(config & flag > 0)
+    }
+
+    /// Enables or disables a monitoring channel on the INA3221
+    pub async fn set_channel_enabled(
+        &mut self,
+        channel: Channel,
+        enabled: bool,
+    ) -> Result<(), Error<E>> {
+        let config = self.get_configuration().await?;
+        let new_config = common::update_channel_enabled(config, channel, enabled);
+        self.write_register(Register::Configuration, new_config)
+            .await
+    }
+
+    /// Gets the shunt voltage of a specific monitoring channel
+    pub async fn get_shunt_voltage(&mut self, channel: Channel) -> Result<Voltage, Error<E>> {
+        let raw_value = self.read_register(channel.shunt_voltage_register()).await?;
+        Ok(common::decode_shunt_voltage(raw_value))
+    }
+
+    /// Gets the bus voltage of a specific monitoring channel
+    pub async fn get_bus_voltage(&mut self, channel: Channel) -> Result<Voltage, Error<E>> {
+        let raw_value = self.read_register(channel.bus_voltage_register()).await?;
+        Ok(common::decode_bus_voltage(raw_value))
+    }
+
+    /// Gets the configured shunt resistance of a specific monitoring channel
+    pub fn get_shunt_resistance(&self, channel: Channel) -> Option<Resistance> {
+        self.shunts.get(channel)
+    }
+
+    /// Sets the shunt resistance of a specific monitoring channel
+    ///
+    /// This is only stored in the driver and is used to calculate the current and power
+    /// of the channel, a resistance of zero is treated as not configured
+    pub fn set_shunt_resistance(&mut self, channel: Channel, resistance: Resistance) {
+        self.shunts.set(channel, resistance);
+    }
+
+    /// Gets the load voltage of a specific monitoring channel
+    ///
+    /// This is the sum of the bus and shunt voltages of that channel
+    pub async fn get_load_voltage(&mut self, channel: Channel) -> Result<Voltage, Error<E>> {
+        let shunt_voltage = self.get_shunt_voltage(channel).await?;
+        let bus_voltage = self.get_bus_voltage(channel).await?;
+        Ok(bus_voltage + shunt_voltage)
+    }
+
+    /// Gets the current draw of a specific monitoring channel
+    ///
+    /// Returns `Error::MissingShuntResistance` if no shunt resistance is configured for that channel
+    pub async fn get_current(&mut self, channel: Channel) -> Result<Current, Error<E>> {
+        self.shunts.milli_ohms(channel)?;
+        let shunt_voltage = self.get_shunt_voltage(channel).await?;
+        self.shunts.current(channel, shunt_voltage)
+    }
+
+    /// Gets the power draw of a specific monitoring channel
+    ///
+    /// Returns `Error::MissingShuntResistance` if no shunt resistance is configured for that channel
+    pub async fn get_power(&mut self, channel: Channel) -> Result<Power, Error<E>> {
+        self.shunts.milli_ohms(channel)?;
+        let shunt_voltage = self.get_shunt_voltage(channel).await?;
+        let bus_voltage = self.get_bus_voltage(channel).await?;
+        self.shunts.power(channel, shunt_voltage, bus_voltage)
+    }
+
+    /// Reads the measurements of all enabled monitoring channels
+    ///
+    /// Disabled channels are skipped, and the current and power are only included
+    /// for channels with a configured shunt resistance
+    pub async fn read_all(&mut self) -> Result<Snapshot, Error<E>> {
+        let config = self.get_configuration().await?;
+        let mut snapshot = SnapshotBuilder::new(config);
+
+        let burst = if self.burst && snapshot.has_enabled() {
+            Some(self.read_burst().await?)
+        } else {
+            None
+        };
+
+        for channel in Channel::ALL {
+            if !snapshot.is_enabled(channel) {
+                continue;
+            }
+
+            let (shunt_voltage, bus_voltage) = match &burst {
+                Some(burst) => burst.voltages(channel),
+                None => (
+                    self.get_shunt_voltage(channel).await?,
+                    self.get_bus_voltage(channel).await?,
+                ),
+            };
+            snapshot.set(
+                channel,
+                self.shunts.reading(channel, shunt_voltage, bus_voltage),
+            );
+        }
+
+        Ok(snapshot.build())
+    }
+
+    /// Reads the measurements of all enabled monitoring channels once a conversion is ready
+    ///
+    /// Returns `None` if the conversion ready flag is not set, otherwise the measurements are read
+    /// with `read_all()`. In continuous mode the INA3221 keeps converting while they are read, so
+    /// the channels may come from different cycles. Use `measure_once()` if they must match.
+    ///
+    /// Any alert flags read while checking for the conversion are kept and returned by the next
+    /// call to `read_alert_flags()`
+    pub async fn read_all_if_ready(&mut self) -> Result<Option<Snapshot>, Error<E>> {
+        let flags = self.read_mask_enable().await?;
+        if !flags.contains(MaskEnableFlags::CONVERSION_READY) {
+            return Ok(None);
+        }
+
+        self.read_all().await.map(Some)
+    }
+
+    /// Triggers a single measurement and waits for it to complete
+    ///
+    /// The conversion ready flag is polled until the measurement completes, returning
+    /// `Error::Timeout` if it takes more than twice the expected time based on the configured
+    /// averaging and conversion times. Returns `Error::NoChannelsEnabled` without triggering a
+    /// measurement if every channel is disabled, or `Error::Delay` if the delay between polls
+    /// fails. The device powers down once the measurement completes.
+    pub async fn measure_once<D: DelayUs>(&mut self, delay: &mut D) -> Result<Snapshot, Error<E>> {
+        // Writing the configuration triggers the measurement and clears the conversion ready flag
+        let config = common::trigger_config(self.read_config().await?);
+        let mut timer = ConversionTimer::new(&config)?;
+        self.write_config(&config).await?;
+
+        delay
+            .delay_us(timer.initial_delay())
+            .await
+            .map_err(|_| Error::Delay)?;
+
+        while !self
+            .read_mask_enable()
+            .await?
+            .contains(MaskEnableFlags::CONVERSION_READY)
+        {
+            delay
+                .delay_us(timer.next_delay()?)
+                .await
+                .map_err(|_| Error::Delay)?;
+        }
+
+        self.read_all().await
+    }
+
+    /// Gets the critical alert limit of a specific monitoring channel
+    ///
+    /// This is the shunt voltage limit that will trigger a critical alert on that channel
+    pub async fn get_critical_alert_limit(
+        &mut self,
+        channel: Channel,
+    ) -> Result<Voltage, Error<E>> {
+        let raw_value = self
+            .read_register(channel.critical_alert_limit_register())
+            .await?;
+        Ok(common::decode_shunt_voltage(raw_value))
+    }
+
+    /// Sets the critical alert limit for a specific monitoring channel
+    ///
+    /// This is the shunt voltage limit that will trigger a critical alert on that channel
+    pub async fn set_critical_alert_limit(
+        &mut self,
+        channel: Channel,
+        voltage_limit: Voltage,
+    ) -> Result<(), Error<E>> {
+        let raw_value = common::encode_shunt_voltage(voltage_limit)?;
+        self.write_register(channel.critical_alert_limit_register(), raw_value)
+            .await
+    }
+
+    /// Gets the critical alert limit of a specific monitoring channel as a current
+    ///
+    /// Returns `Error::MissingShuntResistance` if no shunt resistance is configured for that channel
+    pub async fn get_critical_current_limit(
+        &mut self,
+        channel: Channel,
+    ) -> Result<Current, Error<E>> {
+        self.shunts.milli_ohms(channel)?;
+        let voltage_limit = self.get_critical_alert_limit(channel).await?;
+        self.shunts.current(channel, voltage_limit)
+    }
+
+    /// Sets the critical alert limit for a specific monitoring channel as a current
+    ///
+    /// Returns `Error::MissingShuntResistance` if no shunt resistance is configured for that channel
+    pub async fn set_critical_current_limit(
+        &mut self,
+        channel: Channel,
+        current_limit: Current,
+    ) -> Result<(), Error<E>> {
+        let voltage_limit = self.shunts.shunt_voltage(channel, current_limit)?;
+        self.set_critical_alert_limit(channel, voltage_limit).await
+    }
+
+    /// Sets the critical alert latch behavior for the warning alert pin
+    ///
+    /// If enabled, the critical alert pin will latch until the warning alert is cleared
+    pub async fn set_critical_alert_latch(&mut self, enabled: bool) -> Result<(), Error<E>> {
+        self.set_flag(MaskEnableFlags::CRITICAL_ALERT_LATCH, enabled)
+            .await
+    }
+
+    /// Gets the warning alert limit of a specific monitoring channel
+    ///
+    /// This is the shunt voltage limit that will trigger a warning alert on that channel
+    pub async fn get_warning_alert_limit(&mut self, channel: Channel) -> Result<Voltage, Error<E>> {
+        let raw_value = self
+            .read_register(channel.warning_alert_limit_register())
+            .await?;
+        Ok(common::decode_shunt_voltage(raw_value))
+    }
+
+    /// Sets the warning alert limit for a specific monitoring channel
+    ///
+    /// This is the shunt voltage limit that will trigger a warning alert on that channel
+    pub async fn set_warning_alert_limit(
+        &mut self,
+        channel: Channel,
+        voltage_limit: Voltage,
+    ) -> Result<(), Error<E>> {
+        let raw_value = common::encode_shunt_voltage(voltage_limit)?;
+        self.write_register(channel.warning_alert_limit_register(), raw_value)
+            .await
+    }
+
+    /// Gets the warning alert limit of a specific monitoring channel as a current
+    ///
+    /// Returns `Error::MissingShuntResistance` if no shunt resistance is configured for that channel
+    pub async fn get_warning_current_limit(
+        &mut self,
+        channel: Channel,
+    ) -> Result<Current, Error<E>> {
+        self.shunts.milli_ohms(channel)?;
+        let voltage_limit = self.get_warning_alert_limit(channel).await?;
+        self.shunts.current(channel, voltage_limit)
+    }
+
+    /// Sets the warning alert limit for a specific monitoring channel as a current
+    ///
+    /// Returns `Error::MissingShuntResistance` if no shunt resistance is configured for that channel
+    pub async fn set_warning_current_limit(
+        &mut self,
+        channel: Channel,
+        current_limit: Current,
+    ) -> Result<(), Error<E>> {
+        let voltage_limit = self.shunts.shunt_voltage(channel, current_limit)?;
+        self.set_warning_alert_limit(channel, voltage_limit).await
+    }
+
+    /// Sets the warning alert latch behavior for the warning alert pin
+    ///
+    /// If enabled, the warning alert pin will latch until the warning alert is cleared
+    pub async fn set_warning_alert_latch(&mut self, enabled: bool) -> Result<(), Error<E>> {
+        self.set_flag(MaskEnableFlags::WARNING_ALERT_LATCH, enabled)
+            .await
+    }
+
+    /// Checks if a monitoring channel is included in the shunt voltage summation
+    pub async fn is_summation_channel_enabled(
+        &mut self,
+        channel: Channel,
+    ) -> Result<bool, Error<E>> {
+        let control = self.get_alert_control().await?;
+        Ok(control.contains(channel.summation_control_flag()))
+    }
+
+    /// Includes or excludes a monitoring channel from the shunt voltage summation
+    pub async fn set_summation_channel_enabled(
+        &mut self,
+        channel: Channel,
+        enabled: bool,
+    ) -> Result<(), Error<E>> {
+        self.set_flag(channel.summation_control_flag(), enabled)
+            .await
+    }
+
+    /// Gets the sum of the shunt voltages of all channels included in the summation
+    pub async fn get_shunt_voltage_sum(&mut self) -> Result<Voltage, Error<E>> {
+        let raw_value = self.read_register(Register::ShuntVoltageSum).await?;
+        Ok(common::decode_shunt_voltage_sum(raw_value))
+    }
+
+    /// Gets the summation alert limit
+    ///
+    /// This is the summed shunt voltage limit that will trigger a summation alert
+    pub async fn get_summation_alert_limit(&mut self) -> Result<Voltage, Error<E>> {
+        let raw_value = self.read_register(Register::ShuntVoltageSumLimit).await?;
+        Ok(common::decode_shunt_voltage_sum(raw_value))
+    }
+
+    /// Sets the summation alert limit
+    ///
+    /// This is the summed shunt voltage limit that will trigger a summation alert
+    pub async fn set_summation_alert_limit(
+        &mut self,
+        voltage_limit: Voltage,
+    ) -> Result<(), Error<E>> {
+        let raw_value = common::encode_shunt_voltage_sum(voltage_limit)?;
+        self.write_register(Register::ShuntVoltageSumLimit, raw_value)
+            .await
+    }
+
+    /// Gets the power valid limits of **all** enabled monitoring channels
+    ///
+    /// These are the lower and upper limits (respectively) for the bus voltage that will trigger
+    /// a power valid alert on all enabled channels
+    pub async fn get_power_valid_limits(&mut self) -> Result<(Voltage, Voltage), Error<E>> {
+        let lower_raw_value = self.read_register(Register::PowerValidLowerLimit).await?;
+        let upper_raw_value = self.read_register(Register::PowerValidUpperLimit).await?;
+
+        Ok((
+            common::decode_bus_voltage(lower_raw_value),
+            common::decode_bus_voltage(upper_raw_value),
+        ))
+    }
+
+    /// Sets the power valid limits for **all** enabled monitoring channels
+    ///
+    /// These are the upper and lower limits for the bus voltage that will trigger a power valid alert
+    /// on all enabled channels
+    pub async fn set_power_valid_limits(
+        &mut self,
+        lower_limit: Voltage,
+        upper_limit: Voltage,
+    ) -> Result<(), Error<E>> {
+        let lower_raw_value = common::encode_bus_voltage(lower_limit)?;
+        let upper_raw_value = common::encode_bus_voltage(upper_limit)?;
+
+        self.write_register(Register::PowerValidLowerLimit, lower_raw_value)
+            .await?;
+        self.write_register(Register::PowerValidUpperLimit, upper_raw_value)
+            .await
+    }
+
+    /// Reads the alert flags from the INA3221
+    ///
+    /// Reading clears the alert flags on the device, so the driver keeps them until acknowledged.
+    /// If `preserve` is set to `true`, the alert flags are kept and returned again by the next read,
+    /// otherwise they are acknowledged and cleared
+    pub async fn read_alert_flags(&mut self, preserve: bool) -> Result<MaskEnableFlags, Error<E>> {
+        self.read_flags(preserve).await
+    }
+
+    /// Reads the alert status from the INA3221 without acknowledging the alerts
+    ///
+    /// This reports the same alerts as `read_alert_flags(true)`, but without the latch
+    /// and summation control flags
+    pub async fn read_alert_status(&mut self) -> Result<AlertStatus, Error<E>> {
+        let flags = self.read_flags(true).await?;
+        Ok(AlertStatus::from(flags))
+    }
+
+    /// Reads and acknowledges the alert status from the INA3221
+    ///
+    /// The returned alerts are cleared, so they are only reported again if raised by a later
+    /// conversion
+    pub async fn acknowledge_alerts(&mut self) -> Result<AlertStatus, Error<E>> {
+        let flags = self.read_flags(false).await?;
+        Ok(AlertStatus::from(flags))
+    }
+
+    /// Gets the latch and summation control flags
+    ///
+    /// These are served from the driver's copy of the Mask/Enable register, which is only read
+    /// the first time if the flags are not known yet. Any alerts cleared by that read are kept
+    /// until acknowledged
+    pub async fn get_alert_control(&mut self) -> Result<MaskEnableFlags, Error<E>> {
+        match self.alerts.control() {
+            Some(control) => Ok(control),
+            None => Ok(self.read_mask_enable().await? & MaskEnableFlags::CONTROL_FLAGS),
+        }
+    }
+
+    /// Sets the latch and summation control flags
+    ///
+    /// Any status flags in `control` are ignored, as they are read-only on the INA3221,
+    /// and any pending alerts are kept until acknowledged
+    pub async fn set_alert_control(&mut self, control: MaskEnableFlags) -> Result<(), Error<E>> {
+        let control = control & MaskEnableFlags::CONTROL_FLAGS;
+        self.write_register(Register::MaskEnable, control.bits())
+            .await?;
+        self.alerts.set_control(control);
+        Ok(())
+    }
+
+    /// Waits for the critical alert pin to fire, then reads which alerts were raised
+    ///
+    /// The critical pin is raised by the critical alert limits and the summation alert limit
+    pub async fn wait_for_critical_alert<P: Wait>(
+        &mut self,
+        pin: &mut P,
+    ) -> Result<AlertEvent, Error<E>> {
+        self.wait_for_alert(AlertLine::Critical, pin).await
+    }
+
+    /// Waits for the warning alert pin to fire, then reads which alerts were raised
+    pub async fn wait_for_warning_alert<P: Wait>(
+        &mut self,
+        pin: &mut P,
+    ) -> Result<AlertEvent, Error<E>> {
+        self.wait_for_alert(AlertLine::Warning, pin).await
+    }
+
+    /// Waits for the power valid pin to fall, then reads the alert flags
+    ///
+    /// The power valid pin falls when any enabled bus voltage drops out of the power valid limits
+    pub async fn wait_for_power_valid_alert<P: Wait>(
+        &mut self,
+        pin: &mut P,
+    ) -> Result<AlertEvent, Error<E>> {
+        self.wait_for_alert(AlertLine::PowerValid, pin).await
+    }
+
+    /// Waits for the timing control pin to fall, then reads the alert flags
+    pub async fn wait_for_timing_control_alert<P: Wait>(
+        &mut self,
+        pin: &mut P,
+    ) -> Result<AlertEvent, Error<E>> {
+        self.wait_for_alert(AlertLine::TimingControl, pin).await
+    }
+
+    /// Gets the manufacturer ID from the INA3221
+    ///
+    /// This value is always 0x5449 ('TI' in ASCII), or at least should be for genuine INA3221s
+    pub async fn get_manufacturer_id(&mut self) -> Result<u16, Error<E>> {
+        self.read_register(Register::ManufacturerId).await
+    }
+
+    /// Gets the die ID from the INA3221
+    ///
+    /// This value is always 0x3220, or at least should be for genuine INA3221s
+    pub async fn get_die_id(&mut self) -> Result<u16, Error<E>> {
+        self.read_register(Register::DieId).await
+    }
+
+    /// Resets the INA3221
+    ///
+    /// This clears all configuration bits and sets the default configuration
+    pub async fn reset(&mut self) -> Result<(), Error<E>> {
+        let config = self.read_register(Register::Configuration).await?;
+        self.write_register(Register::Configuration, common::update_reset(config))
+            .await
+    }
+
+    /// Checks if the register cache is enabled
+    pub fn is_cache_enabled(&self) -> bool {
+        self.cache.is_enabled()
+    }
+
+    /// Enables or disables the register cache
+    ///
+    /// When enabled, the configuration and limit registers are only read from the INA3221 once
+    /// and then served from the cache, so setters only need to write the register.
+    /// Enabling or disabling the cache discards any cached values
+    pub fn set_cache_enabled(&mut self, enabled: bool) {
+        self.cache.set_enabled(enabled);
+    }
+
+    /// Reads the configuration, limit, and alert control registers into the cache
+    ///
+    /// This should be called after the INA3221 has been changed by something other than the
+    /// driver, such as a power cycle or another bus controller
+    pub async fn sync(&mut self) -> Result<(), Error<E>> {
+        self.invalidate();
+
+        for register in RegisterCache::registers() {
+            self.read_register(register).await?;
+        }
+
+        self.read_mask_enable().await?;
+        Ok(())
+    }
+
+    /// Discards the cached registers and alert control flags, so they are read from the INA3221 the
+    /// next time they are used
+    pub fn invalidate(&mut self) {
+        self.cache.invalidate();
+        self.alerts.invalidate_control();
+    }
+
+    /// Checks if burst reads are enabled
+    pub fn is_burst_enabled(&self) -> bool {
+        self.burst
+    }
+
+    /// Enables or disables burst reads of the measurements
+    ///
+    /// When enabled, `read_all()` reads the shunt and bus voltages of every channel in a single
+    /// I2C transaction, so they cannot be interleaved with other traffic on the bus. If that
+    /// transaction fails, the error is returned without falling back to separate reads.
+    /// When disabled, each register is read in its own transaction
+    pub fn set_burst_enabled(&mut self, enabled: bool) {
+        self.burst = enabled;
+    }
+
+    /// Checks if waiting for an alert returns straight away when the pin is already asserted
+    pub fn is_alert_level_checked(&self) -> bool {
+        self.alert_level_checked
+    }
+
+    /// Sets whether waiting for an alert returns straight away when the pin is already asserted
+    ///
+    /// When disabled, the wait is always for the next falling edge of the pin. Only enable this
+    /// when the awaited alerts are latched, as a transparent alert keeps the pin asserted while
+    /// the limit is exceeded
+    pub fn set_alert_level_checked(&mut self, checked: bool) {
+        self.alert_level_checked = checked;
+    }
+
+    async fn verify_identity(&mut self) -> Result<(), Error<E>> {
+        let manufacturer_id = self.get_manufacturer_id().await?;
+        let die_id = self.get_die_id().await?;
+        common::verify_identity(manufacturer_id, die_id)
+    }
+
+    async fn read_register(&mut self, register: Register) -> Result<u16, Error<E>> {
+        if let Some(value) = self.cache.get(register) {
+            return Ok(value);
+        }
+
+        // Use a repeated start, so the pointer cannot be moved by another controller in between
+        let mut buffer: [u8; 2] = [0x00; 2];
+        self.i2c
+            .write_read(self.address.bits(), &[register as u8], &mut buffer)
+            .await
+            .map_err(Error::I2c)?;
+
+        let value = common::decode_register_read(buffer);
+        self.cache.store(register, value);
+        Ok(value)
+    }
+
+    async fn read_burst(&mut self) -> Result<BurstRead, Error<E>> {
+        let mut burst = BurstRead::new();
+        self.i2c
+            .transaction(self.address.bits(), &mut burst.operations())
+            .await
+            .map_err(Error::I2c)?;
+
+        Ok(burst)
+    }
+
+    async fn write_register(&mut self, register: Register, value: u16) -> Result<(), Error<E>> {
+        let buffer = common::encode_register_write(register, value);
+        self.i2c
+            .write(self.address.bits(), &buffer)
+            .await
+            .map_err(Error::I2c)?;
+
+        self.cache.store(register, value);
+        if common::is_reset(register, value) {
+            // The reset also clears the latch and summation control flags
+            self.alerts.set_control(MaskEnableFlags::empty());
+        }
+
+        Ok(())
+    }
+
+    async fn set_config_field(&mut self, shift: u16, value: u16) -> Result<(), Error<E>> {
+        let config = self.get_configuration().await?;
+        let new_config = common::update_config_field(config, shift, value);
+        self.write_register(Register::Configuration, new_config)
+            .await
+    }
+
+    async fn read_mask_enable(&mut self) -> Result<MaskEnableFlags, Error<E>> {
+        // Reading clears the alert flags, so keep them until the caller reads them
+        let flags = self.read_register(Register::MaskEnable).await?;
+        Ok(self.alerts.record(flags))
+    }
+
+    async fn read_flags(&mut self, preserve: bool) -> Result<MaskEnableFlags, Error<E>> {
+        let flags = self.read_mask_enable().await?;
+        let alerts = match preserve {
+            true => self.alerts.peek(),
+            false => self.alerts.take(),
+        };
+        Ok(flags | alerts)
+    }
+
+    async fn wait_for_alert<P: Wait>(
+        &mut self,
+        line: AlertLine,
+        pin: &mut P,
+    ) -> Result<AlertEvent, Error<E>> {
+        // The alert pins are open-drain and pulled low when asserted
+        let asserted = match self.alert_level_checked {
+            true => pin.wait_for_low().await,
+            false => pin.wait_for_falling_edge().await,
+        };
+        asserted.map_err(|_| Error::Pin)?;
+
+        // Only acknowledge the alerts of the awaited line, so those of other lines are kept
+        let flags = self.read_flags(true).await?;
+        self.alerts.acknowledge(line.flags());
+        Ok(AlertEvent::new(line, flags))
+    }
+
+    async fn set_flag(&mut self, flag: MaskEnableFlags, enabled: bool) -> Result<(), Error<E>> {
+        let mut control = self.get_alert_control().await?;
+        control.set(flag, enabled);
+        self.set_alert_control(control).await
+    }
+}
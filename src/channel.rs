@@ -0,0 +1,116 @@
+use crate::config::{CHANNEL_1_FLAG, CHANNEL_2_FLAG, CHANNEL_3_FLAG};
+use crate::registers::Register;
+use crate::{Error, MaskEnableFlags};
+use core::convert::TryFrom;
+
+/// Represents one of the three monitoring channels of the INA3221
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Channel {
+    /// Channel 1 (IN1+ and IN1-)
+    Ch1 = 0,
+    /// Channel 2 (IN2+ and IN2-)
+    Ch2 = 1,
+    /// Channel 3 (IN3+ and IN3-)
+    Ch3 = 2,
+}
+
+impl Channel {
+    /// All three channels in order, useful for iterating over every channel
+    pub const ALL: [Channel; 3] = [Channel::Ch1, Channel::Ch2, Channel::Ch3];
+
+    /// Gets the zero-based index of the channel, so channel 1 is index 0
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+
+    /// Gets the critical alert status flag of the channel
+    pub fn critical_alert_flag(&self) -> MaskEnableFlags {
+        match self {
+            Channel::Ch1 => MaskEnableFlags::CRITICAL_ALERT_1,
+            Channel::Ch2 => MaskEnableFlags::CRITICAL_ALERT_2,
+            Channel::Ch3 => MaskEnableFlags::CRITICAL_ALERT_3,
+        }
+    }
+
+    /// Gets the warning alert status flag of the channel
+    pub fn warning_alert_flag(&self) -> MaskEnableFlags {
+        match self {
+            Channel::Ch1 => MaskEnableFlags::WARNING_ALERT_1,
+            Channel::Ch2 => MaskEnableFlags::WARNING_ALERT_2,
+            Channel::Ch3 => MaskEnableFlags::WARNING_ALERT_3,
+        }
+    }
+
+    /// Gets the summation control flag of the channel
+    pub fn summation_control_flag(&self) -> MaskEnableFlags {
+        match self {
+            Channel::Ch1 => MaskEnableFlags::SUMMATION_CONTROL_1,
+            Channel::Ch2 => MaskEnableFlags::SUMMATION_CONTROL_2,
+            Channel::Ch3 => MaskEnableFlags::SUMMATION_CONTROL_3,
+        }
+    }
+
+    pub(crate) fn enable_flag(&self) -> u16 {
+        match self {
+            Channel::Ch1 => CHANNEL_1_FLAG,
+            Channel::Ch2 => CHANNEL_2_FLAG,
+            Channel::Ch3 => CHANNEL_3_FLAG,
+        }
+    }
+
+    pub(crate) fn shunt_voltage_register(&self) -> Register {
+        match self {
+            Channel::Ch1 => Register::ShuntVoltage1,
+            Channel::Ch2 => Register::ShuntVoltage2,
+            Channel::Ch3 => Register::ShuntVoltage3,
+        }
+    }
+
+    pub(crate) fn bus_voltage_register(&self) -> Register {
+        match self {
+            Channel::Ch1 => Register::BusVoltage1,
+            Channel::Ch2 => Register::BusVoltage2,
+            Channel::Ch3 => Register::BusVoltage3,
+        }
+    }
+
+    pub(crate) fn critical_alert_limit_register(&self) -> Register {
+        match self {
+            Channel::Ch1 => Register::CriticalAlertLimit1,
+            Channel::Ch2 => Register::CriticalAlertLimit2,
+            Channel::Ch3 => Register::CriticalAlertLimit3,
+        }
+    }
+
+    pub(crate) fn warning_alert_limit_register(&self) -> Register {
+        match self {
+            Channel::Ch1 => Register::WarningAlertLimit1,
+            Channel::Ch2 => Register::WarningAlertLimit2,
+            Channel::Ch3 => Register::WarningAlertLimit3,
+        }
+    }
+}
+
+/// Error returned when converting a channel index outside of the range of 0-2 into a [`Channel`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidChannel(pub u8);
+
+impl TryFrom<u8> for Channel {
+    type Error = InvalidChannel;
+
+    /// Converts a zero-based channel index, so index 0 is channel 1
+    fn try_from(index: u8) -> Result<Self, Self::Error> {
+        match index {
+            0 => Ok(Channel::Ch1),
+            1 => Ok(Channel::Ch2),
+            2 => Ok(Channel::Ch3),
+            _ => Err(InvalidChannel(index)),
+        }
+    }
+}
+
+impl<E> From<InvalidChannel> for Error<E> {
+    fn from(_: InvalidChannel) -> Self {
+        Error::InvalidChannel
+    }
+}
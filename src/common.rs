@@ -0,0 +1,488 @@
+use crate::config::{
+    CHANNEL_1_FLAG, CHANNEL_2_FLAG, CHANNEL_3_FLAG, DEFAULT_CONFIGURATION, FIELD_MASK, MODE_MASK,
+    RESET_FLAG,
+};
+use crate::registers::Register;
+use crate::{
+    helpers, Channel, ChannelReading, Configuration, Error, MaskEnableFlags, Measurement,
+    OperatingMode, Power, Snapshot,
+};
+use core::cell::Cell;
+use hal::i2c::{self, ErrorKind, Operation};
+use ohms::{Current, Resistance, Voltage};
+
+// Register encoding and decoding shared by the blocking and async drivers,
+// so both stay in lockstep and only differ in how the I2C bus is accessed.
+
+const CONVERSION_POLL_DIVISOR: u32 = 10;
+const MIN_CONVERSION_POLL_MICROS: u32 = 100;
+
+/// Registers kept by the register cache, these only change when written by the driver
+const CACHED_REGISTERS: [Register; 10] = [
+    Register::Configuration,
+    Register::CriticalAlertLimit1,
+    Register::WarningAlertLimit1,
+    Register::CriticalAlertLimit2,
+    Register::WarningAlertLimit2,
+    Register::CriticalAlertLimit3,
+    Register::WarningAlertLimit3,
+    Register::ShuntVoltageSumLimit,
+    Register::PowerValidUpperLimit,
+    Register::PowerValidLowerLimit,
+];
+
+/// Values of the cached registers after a reset, in the same order
+const CACHED_RESET_VALUES: [u16; 10] = [
+    DEFAULT_CONFIGURATION,
+    0x7FF8,
+    0x7FF8,
+    0x7FF8,
+    0x7FF8,
+    0x7FF8,
+    0x7FF8,
+    0x7FFE,
+    0x2710,
+    0x2328,
+];
+
+/// Manufacturer ID reported by genuine INA3221s ('TI' in ASCII)
+const MANUFACTURER_ID: u16 = 0x5449;
+/// Die ID reported by genuine INA3221s
+const DIE_ID: u16 = 0x3220;
+
+pub fn verify_identity<E>(manufacturer_id: u16, die_id: u16) -> Result<(), Error<E>> {
+    match (manufacturer_id, die_id) {
+        (MANUFACTURER_ID, DIE_ID) => Ok(()),
+        _ => Err(Error::UnexpectedDevice {
+            manufacturer_id,
+            die_id,
+        }),
+    }
+}
+
+/// Checks the result of probing an address while scanning the bus
+///
+/// Addresses that do not acknowledge or belong to other devices are not found,
+/// but any other I2C error is returned
+pub fn is_device_found<E: i2c::Error>(probe: Result<(), Error<E>>) -> Result<bool, Error<E>> {
+    match probe {
+        Ok(()) => Ok(true),
+        Err(Error::UnexpectedDevice { .. }) => Ok(false),
+        Err(Error::I2c(error)) if matches!(error.kind(), ErrorKind::NoAcknowledge(_)) => Ok(false),
+        Err(error) => Err(error),
+    }
+}
+
+pub fn encode_register_write(register: Register, value: u16) -> [u8; 3] {
+    // Convert from little endian to big endian
+    let msb = ((value >> 8) & 0xFF) as u8;
+    let lsb = (value & 0xFF) as u8;
+
+    [register as u8, msb, lsb]
+}
+
+pub fn decode_register_read(buffer: [u8; 2]) -> u16 {
+    // Convert from big endian 16-bit word
+    ((buffer[0] as u16) << 8) + buffer[1] as u16
+}
+
+pub fn update_config_field(config: u16, shift: u16, value: u16) -> u16 {
+    (config & !(FIELD_MASK << shift)) | ((value & FIELD_MASK) << shift)
+}
+
+pub fn update_mode(config: u16, mode: OperatingMode) -> u16 {
+    (config & !MODE_MASK) | mode.bits()
+}
+
+pub fn update_channel_enabled(config: u16, channel: Channel, enabled: bool) -> u16 {
+    // Toggle the channel bit in the configuration
+    match enabled {
+        true => config | channel.enable_flag(),
+        false => config & !channel.enable_flag(),
+    }
+}
+
+pub fn update_channels_enabled<E>(config: u16, enabled: &[bool]) -> Result<u16, Error<E>> {
+    if enabled.len() < 3 {
+        return Err(Error::InvalidChannel);
+    }
+
+    let mut new_config = config & !(CHANNEL_1_FLAG | CHANNEL_2_FLAG | CHANNEL_3_FLAG);
+    for channel in Channel::ALL {
+        new_config = update_channel_enabled(new_config, channel, enabled[channel.index()]);
+    }
+
+    Ok(new_config)
+}
+
+pub fn decode_channels_enabled<E>(config: u16, statuses: &mut [bool]) -> Result<(), Error<E>> {
+    if statuses.len() < 3 {
+        return Err(Error::InvalidChannel);
+    }
+
+    for channel in Channel::ALL {
+        statuses[channel.index()] = config & channel.enable_flag() > 0;
+    }
+
+    Ok(())
+}
+
+pub fn is_reset(register: Register, value: u16) -> bool {
+    register == Register::Configuration && value & RESET_FLAG > 0
+}
+
+pub fn update_reset(config: u16) -> u16 {
+    config | RESET_FLAG
+}
+
+pub fn trigger_config(config: Configuration) -> Configuration {
+    // Keep the selected measurements, or measure both when powered down
+    let measurement = config
+        .mode()
+        .measurement()
+        .unwrap_or(Measurement::ShuntAndBus);
+
+    config
+        .to_builder()
+        .mode(OperatingMode::Triggered(measurement))
+        .build()
+}
+
+pub fn decode_shunt_voltage(value: u16) -> Voltage {
+    // LSB = 40uV, meaning the value is downscaled 40:1
+    Voltage::from_micro_volts(helpers::decode_shunt_voltage(value))
+}
+
+pub fn encode_shunt_voltage<E>(voltage: Voltage) -> Result<u16, Error<E>> {
+    helpers::encode_shunt_voltage(voltage.micro_volts()).ok_or(Error::LimitOutOfRange)
+}
+
+pub fn decode_bus_voltage(value: u16) -> Voltage {
+    // LSB = 8mV (8000uV), meaning the value is downscaled 8:1
+    Voltage::from_micro_volts(helpers::decode_bus_voltage(value))
+}
+
+pub fn encode_bus_voltage<E>(voltage: Voltage) -> Result<u16, Error<E>> {
+    helpers::encode_bus_voltage(voltage.micro_volts()).ok_or(Error::LimitOutOfRange)
+}
+
+pub fn decode_shunt_voltage_sum(value: u16) -> Voltage {
+    // LSB = 40uV, stored in bits 15-1 unlike the per-channel registers
+    Voltage::from_micro_volts(helpers::decode_shunt_voltage_sum(value))
+}
+
+pub fn encode_shunt_voltage_sum<E>(voltage: Voltage) -> Result<u16, Error<E>> {
+    helpers::encode_shunt_voltage_sum(voltage.micro_volts()).ok_or(Error::LimitOutOfRange)
+}
+
+/// Shunt resistances configured for each channel, used to calculate current and power
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Shunts {
+    resistances: [Option<Resistance>; 3],
+}
+
+impl Shunts {
+    pub fn get(&self, channel: Channel) -> Option<Resistance> {
+        self.resistances[channel.index()]
+    }
+
+    pub fn set(&mut self, channel: Channel, resistance: Resistance) {
+        self.resistances[channel.index()] = Some(resistance);
+    }
+
+    pub fn milli_ohms<E>(&self, channel: Channel) -> Result<u32, Error<E>> {
+        match self.resistances[channel.index()] {
+            Some(resistance) if resistance.milli_ohms() > 0 => Ok(resistance.milli_ohms()),
+            _ => Err(Error::MissingShuntResistance),
+        }
+    }
+
+    pub fn current<E>(
+        &self,
+        channel: Channel,
+        shunt_voltage: Voltage,
+    ) -> Result<Current, Error<E>> {
+        let shunt_resistance = self.milli_ohms(channel)?;
+        let microamps = helpers::calculate_current(shunt_voltage.micro_volts(), shunt_resistance);
+        Ok(Current::from_micro_amps(microamps))
+    }
+
+    pub fn power<E>(
+        &self,
+        channel: Channel,
+        shunt_voltage: Voltage,
+        bus_voltage: Voltage,
+    ) -> Result<Power, Error<E>> {
+        let current = self.current(channel, shunt_voltage)?;
+        let load_voltage = bus_voltage + shunt_voltage;
+
+        let microwatts = helpers::calculate_power(current.micro_amps(), load_voltage.micro_volts());
+        Ok(Power::from_micro_watts(microwatts))
+    }
+
+    pub fn shunt_voltage<E>(
+        &self,
+        channel: Channel,
+        current: Current,
+    ) -> Result<Voltage, Error<E>> {
+        let shunt_resistance = self.milli_ohms(channel)?;
+        let microvolts = helpers::calculate_shunt_voltage(current.micro_amps(), shunt_resistance)
+            .ok_or(Error::LimitOutOfRange)?;
+        Ok(Voltage::from_micro_volts(microvolts))
+    }
+
+    pub fn reading(
+        &self,
+        channel: Channel,
+        shunt_voltage: Voltage,
+        bus_voltage: Voltage,
+    ) -> ChannelReading {
+        ChannelReading {
+            shunt_voltage,
+            bus_voltage,
+            current: self.current::<()>(channel, shunt_voltage).ok(),
+            power: self.power::<()>(channel, shunt_voltage, bus_voltage).ok(),
+        }
+    }
+}
+
+/// Builds a snapshot from the readings of the channels enabled in the configuration
+pub struct SnapshotBuilder {
+    config: u16,
+    readings: [Option<ChannelReading>; 3],
+}
+
+impl SnapshotBuilder {
+    pub fn new(config: u16) -> SnapshotBuilder {
+        SnapshotBuilder {
+            config,
+            readings: [None; 3],
+        }
+    }
+
+    pub fn is_enabled(&self, channel: Channel) -> bool {
+        self.config & channel.enable_flag() > 0
+    }
+
+    pub fn has_enabled(&self) -> bool {
+        Channel::ALL.iter().any(|channel| self.is_enabled(*channel))
+    }
+
+    pub fn set(&mut self, channel: Channel, reading: ChannelReading) {
+        self.readings[channel.index()] = Some(reading);
+    }
+
+    pub fn build(self) -> Snapshot {
+        Snapshot::new(self.readings)
+    }
+}
+
+/// Shunt and bus voltage registers of every channel, which are consecutive from 0x01 to 0x06
+const MEASUREMENT_REGISTERS: [Register; 6] = [
+    Register::ShuntVoltage1,
+    Register::BusVoltage1,
+    Register::ShuntVoltage2,
+    Register::BusVoltage2,
+    Register::ShuntVoltage3,
+    Register::BusVoltage3,
+];
+
+/// Reads the shunt and bus voltage registers of every channel in a single transaction
+///
+/// The register pointer does not auto-increment, so a longer read would return the same register
+/// over and over. Instead the pointer is written before every register, using repeated starts
+pub struct BurstRead {
+    pointers: [[u8; 1]; 6],
+    buffers: [[u8; 2]; 6],
+}
+
+impl BurstRead {
+    pub fn new() -> BurstRead {
+        BurstRead {
+            pointers: MEASUREMENT_REGISTERS.map(|register| [register as u8]),
+            buffers: [[0x00; 2]; 6],
+        }
+    }
+
+    pub fn operations(&mut self) -> [Operation<'_>; 12] {
+        let [p1, p2, p3, p4, p5, p6] = &self.pointers;
+        let [b1, b2, b3, b4, b5, b6] = &mut self.buffers;
+        [
+            Operation::Write(p1),
+            Operation::Read(b1),
+            Operation::Write(p2),
+            Operation::Read(b2),
+            Operation::Write(p3),
+            Operation::Read(b3),
+            Operation::Write(p4),
+            Operation::Read(b4),
+            Operation::Write(p5),
+            Operation::Read(b5),
+            Operation::Write(p6),
+            Operation::Read(b6),
+        ]
+    }
+
+    /// Decodes the shunt and bus voltages of a specific channel
+    pub fn voltages(&self, channel: Channel) -> (Voltage, Voltage) {
+        let index = channel.index() * 2;
+        let shunt_voltage = decode_register_read(self.buffers[index]);
+        let bus_voltage = decode_register_read(self.buffers[index + 1]);
+        (
+            decode_shunt_voltage(shunt_voltage),
+            decode_bus_voltage(bus_voltage),
+        )
+    }
+}
+
+/// Driver copy of the Mask/Enable register
+///
+/// Reading the register clears the alert flags, so they are kept until acknowledged,
+/// and the control flags are kept so they can be read without touching the alert flags
+#[derive(Debug, Default)]
+pub struct AlertShadow {
+    pending: Cell<u16>,
+    control: Cell<Option<u16>>,
+}
+
+impl AlertShadow {
+    /// Decodes the Mask/Enable register, keeping any alert flags until they are taken
+    pub fn record(&self, value: u16) -> MaskEnableFlags {
+        let flags = MaskEnableFlags::from_bits_truncate(value);
+        let alerts = flags & MaskEnableFlags::ALERT_FLAGS;
+        self.pending.set(self.pending.get() | alerts.bits());
+        self.set_control(flags);
+        flags
+    }
+
+    pub fn peek(&self) -> MaskEnableFlags {
+        MaskEnableFlags::from_bits_truncate(self.pending.get())
+    }
+
+    pub fn take(&self) -> MaskEnableFlags {
+        MaskEnableFlags::from_bits_truncate(self.pending.take())
+    }
+
+    /// Clears only the given alert flags, keeping any others until they are taken
+    #[cfg(feature = "async")]
+    pub fn acknowledge(&self, flags: MaskEnableFlags) {
+        self.pending.set(self.pending.get() & !flags.bits());
+    }
+
+    pub fn control(&self) -> Option<MaskEnableFlags> {
+        self.control.get().map(MaskEnableFlags::from_bits_truncate)
+    }
+
+    pub fn invalidate_control(&self) {
+        self.control.set(None);
+    }
+
+    pub fn set_control(&self, flags: MaskEnableFlags) {
+        let control = flags & MaskEnableFlags::CONTROL_FLAGS;
+        self.control.set(Some(control.bits()));
+    }
+}
+
+/// Optional copy of the registers that only change when written by the driver,
+/// used to skip reading them back over the I2C bus
+#[derive(Debug, Default)]
+pub struct RegisterCache {
+    enabled: bool,
+    values: [Cell<Option<u16>>; 10],
+}
+
+impl RegisterCache {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        self.invalidate();
+    }
+
+    pub fn registers() -> impl Iterator<Item = Register> {
+        CACHED_REGISTERS.into_iter()
+    }
+
+    pub fn get(&self, register: Register) -> Option<u16> {
+        match self.enabled {
+            true => self.slot(register).and_then(Cell::get),
+            false => None,
+        }
+    }
+
+    pub fn store(&self, register: Register, value: u16) {
+        // Setting the reset bit restores every register to its reset value instead
+        if is_reset(register, value) {
+            return self.reset();
+        }
+
+        if let (true, Some(slot)) = (self.enabled, self.slot(register)) {
+            slot.set(Some(value));
+        }
+    }
+
+    pub fn invalidate(&self) {
+        for value in &self.values {
+            value.set(None);
+        }
+    }
+
+    /// Stores the values the cached registers take after a reset
+    pub fn reset(&self) {
+        for (register, value) in CACHED_REGISTERS.into_iter().zip(CACHED_RESET_VALUES) {
+            self.store(register, value);
+        }
+    }
+
+    fn slot(&self, register: Register) -> Option<&Cell<Option<u16>>> {
+        CACHED_REGISTERS
+            .iter()
+            .position(|cached| *cached == register)
+            .map(|index| &self.values[index])
+    }
+}
+
+/// Tracks the time spent waiting for a triggered conversion to complete
+pub struct ConversionTimer {
+    cycle_time: u32,
+    poll_interval: u32,
+    timeout: u32,
+    elapsed: u32,
+}
+
+impl ConversionTimer {
+    /// Times out once twice the expected cycle time has elapsed
+    ///
+    /// Returns `Error::NoChannelsEnabled` if the cycle time is zero, as it would time out at once
+    pub fn new<E>(config: &Configuration) -> Result<ConversionTimer, Error<E>> {
+        let cycle_time = config.cycle_time_micros();
+        if cycle_time == 0 {
+            return Err(Error::NoChannelsEnabled);
+        }
+
+        Ok(ConversionTimer {
+            cycle_time,
+            poll_interval: (cycle_time / CONVERSION_POLL_DIVISOR).max(MIN_CONVERSION_POLL_MICROS),
+            timeout: cycle_time.saturating_mul(2),
+            elapsed: 0,
+        })
+    }
+
+    /// Gets the delay before the first poll, which is the expected cycle time
+    pub fn initial_delay(&mut self) -> u32 {
+        self.elapsed = self.cycle_time;
+        self.cycle_time
+    }
+
+    /// Gets the delay before the next poll, or `Error::Timeout` if the conversion took too long
+    pub fn next_delay<E>(&mut self) -> Result<u32, Error<E>> {
+        if self.elapsed >= self.timeout {
+            return Err(Error::Timeout);
+        }
+
+        self.elapsed = self.elapsed.saturating_add(self.poll_interval);
+        Ok(self.poll_interval)
+    }
+}
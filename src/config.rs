@@ -0,0 +1,291 @@
+use crate::{Averaging, Channel, ConversionTime, OperatingMode};
+
+pub(crate) const RESET_FLAG: u16 = 0x8000;
+pub(crate) const CHANNEL_1_FLAG: u16 = 0x4000;
+pub(crate) const CHANNEL_2_FLAG: u16 = 0x2000;
+pub(crate) const CHANNEL_3_FLAG: u16 = 0x1000;
+pub(crate) const CONTINUOUS_FLAG: u16 = 0x0004;
+pub(crate) const BUS_MEASUREMENT_FLAG: u16 = 0x0002;
+pub(crate) const SHUNT_MEASUREMENT_FLAG: u16 = 0x0001;
+
+pub(crate) const AVERAGING_SHIFT: u16 = 9;
+pub(crate) const BUS_CONVERSION_TIME_SHIFT: u16 = 6;
+pub(crate) const SHUNT_CONVERSION_TIME_SHIFT: u16 = 3;
+pub(crate) const FIELD_MASK: u16 = 0x7;
+pub(crate) const MODE_MASK: u16 = 0x7;
+
+pub(crate) const DEFAULT_CONFIGURATION: u16 = 0x7127;
+
+/// Represents the contents of the Configuration register of the INA3221
+///
+/// Every bit of the register is represented, so converting to and from the raw register value
+/// is lossless. Use [`Configuration::builder()`] to create a configuration fluently, starting
+/// from the power-on default (`0x7127`).
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # use ina3221::prelude::*;
+/// # let mut i2c = ina3221::record::Replay::new("");
+/// # let mut ina = INA3221::new(&mut i2c, Address::Gnd);
+/// let config = Configuration::builder()
+///     .channel_enabled(Channel::Ch3, false)
+///     .averaging(Averaging::Samples64)
+///     .bus_conversion_time(ConversionTime::Micros8244)
+///     .shunt_conversion_time(ConversionTime::Micros8244)
+///     .build();
+///
+/// ina.write_config(&config).unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Configuration {
+    reset: bool,
+    channels_enabled: [bool; 3],
+    averaging: Averaging,
+    bus_conversion_time: ConversionTime,
+    shunt_conversion_time: ConversionTime,
+    continuous: bool,
+    bus_measurement: bool,
+    shunt_measurement: bool,
+}
+
+impl Configuration {
+    /// Creates a builder starting from the power-on default configuration
+    pub fn builder() -> ConfigurationBuilder {
+        ConfigurationBuilder::new()
+    }
+
+    /// Creates a builder starting from this configuration
+    pub fn to_builder(&self) -> ConfigurationBuilder {
+        ConfigurationBuilder::from_config(*self)
+    }
+
+    /// Decodes a configuration from the raw Configuration register value
+    pub fn from_bits(bits: u16) -> Configuration {
+        Configuration {
+            reset: bits & RESET_FLAG > 0,
+            channels_enabled: [
+                bits & CHANNEL_1_FLAG > 0,
+                bits & CHANNEL_2_FLAG > 0,
+                bits & CHANNEL_3_FLAG > 0,
+            ],
+            averaging: Averaging::from_bits(bits >> AVERAGING_SHIFT),
+            bus_conversion_time: ConversionTime::from_bits(bits >> BUS_CONVERSION_TIME_SHIFT),
+            shunt_conversion_time: ConversionTime::from_bits(bits >> SHUNT_CONVERSION_TIME_SHIFT),
+            continuous: bits & CONTINUOUS_FLAG > 0,
+            bus_measurement: bits & BUS_MEASUREMENT_FLAG > 0,
+            shunt_measurement: bits & SHUNT_MEASUREMENT_FLAG > 0,
+        }
+    }
+
+    /// Encodes the configuration as a raw Configuration register value
+    pub fn bits(&self) -> u16 {
+        let mut bits = ((self.averaging as u16) << AVERAGING_SHIFT)
+            | ((self.bus_conversion_time as u16) << BUS_CONVERSION_TIME_SHIFT)
+            | ((self.shunt_conversion_time as u16) << SHUNT_CONVERSION_TIME_SHIFT);
+
+        let flags = [
+            (self.reset, RESET_FLAG),
+            (self.channels_enabled[0], CHANNEL_1_FLAG),
+            (self.channels_enabled[1], CHANNEL_2_FLAG),
+            (self.channels_enabled[2], CHANNEL_3_FLAG),
+            (self.continuous, CONTINUOUS_FLAG),
+            (self.bus_measurement, BUS_MEASUREMENT_FLAG),
+            (self.shunt_measurement, SHUNT_MEASUREMENT_FLAG),
+        ];
+
+        for (enabled, flag) in flags {
+            if enabled {
+                bits |= flag;
+            }
+        }
+
+        bits
+    }
+
+    /// Checks if the reset bit is set
+    ///
+    /// Writing a configuration with this bit set resets the INA3221, the device always reads it back as cleared
+    pub fn is_reset(&self) -> bool {
+        self.reset
+    }
+
+    /// Checks if a monitoring channel is enabled
+    pub fn is_channel_enabled(&self, channel: Channel) -> bool {
+        self.channels_enabled[channel.index()]
+    }
+
+    /// Gets the enabled status for all three channels
+    pub fn channels_enabled(&self) -> [bool; 3] {
+        self.channels_enabled
+    }
+
+    /// Gets the number of samples averaged together for each measurement
+    pub fn averaging(&self) -> Averaging {
+        self.averaging
+    }
+
+    /// Gets the conversion time used for bus voltage measurements
+    pub fn bus_conversion_time(&self) -> ConversionTime {
+        self.bus_conversion_time
+    }
+
+    /// Gets the conversion time used for shunt voltage measurements
+    pub fn shunt_conversion_time(&self) -> ConversionTime {
+        self.shunt_conversion_time
+    }
+
+    /// Gets the operating mode encoded by the continuous, bus and shunt measurement bits
+    pub fn mode(&self) -> OperatingMode {
+        OperatingMode::from_bits(self.bits())
+    }
+
+    /// Gets the time taken by a single measurement cycle in microseconds
+    ///
+    /// This is the time to measure every enabled channel, including averaging,
+    /// based on the nominal conversion times of the INA3221
+    pub fn cycle_time_micros(&self) -> u32 {
+        let mut channel_time = 0;
+        if self.shunt_measurement {
+            channel_time += self.shunt_conversion_time.micros();
+        }
+        if self.bus_measurement {
+            channel_time += self.bus_conversion_time.micros();
+        }
+
+        let enabled_channels = self
+            .channels_enabled
+            .iter()
+            .filter(|&&enabled| enabled)
+            .count();
+        channel_time * self.averaging.samples() as u32 * enabled_channels as u32
+    }
+
+    /// Checks if measurements are performed continuously rather than once
+    pub fn is_continuous(&self) -> bool {
+        self.continuous
+    }
+
+    /// Checks if bus voltage measurements are performed
+    pub fn is_bus_measurement_enabled(&self) -> bool {
+        self.bus_measurement
+    }
+
+    /// Checks if shunt voltage measurements are performed
+    pub fn is_shunt_measurement_enabled(&self) -> bool {
+        self.shunt_measurement
+    }
+}
+
+impl Default for Configuration {
+    /// Creates the power-on default configuration (`0x7127`)
+    fn default() -> Self {
+        Configuration::from_bits(DEFAULT_CONFIGURATION)
+    }
+}
+
+impl From<u16> for Configuration {
+    fn from(bits: u16) -> Self {
+        Configuration::from_bits(bits)
+    }
+}
+
+impl From<Configuration> for u16 {
+    fn from(config: Configuration) -> Self {
+        config.bits()
+    }
+}
+
+/// Fluent builder for a [`Configuration`]
+#[derive(Debug, Clone, Copy)]
+pub struct ConfigurationBuilder {
+    config: Configuration,
+}
+
+impl ConfigurationBuilder {
+    /// Creates a builder starting from the power-on default configuration
+    pub fn new() -> ConfigurationBuilder {
+        ConfigurationBuilder {
+            config: Configuration::default(),
+        }
+    }
+
+    /// Creates a builder starting from an existing configuration
+    pub fn from_config(config: Configuration) -> ConfigurationBuilder {
+        ConfigurationBuilder { config }
+    }
+
+    /// Sets the reset bit, which resets the INA3221 when written
+    pub fn reset(mut self, reset: bool) -> Self {
+        self.config.reset = reset;
+        self
+    }
+
+    /// Enables or disables a monitoring channel
+    pub fn channel_enabled(mut self, channel: Channel, enabled: bool) -> Self {
+        self.config.channels_enabled[channel.index()] = enabled;
+        self
+    }
+
+    /// Enables or disables all three monitoring channels
+    pub fn channels_enabled(mut self, enabled: [bool; 3]) -> Self {
+        self.config.channels_enabled = enabled;
+        self
+    }
+
+    /// Sets the number of samples averaged together for each measurement
+    pub fn averaging(mut self, averaging: Averaging) -> Self {
+        self.config.averaging = averaging;
+        self
+    }
+
+    /// Sets the conversion time used for bus voltage measurements
+    pub fn bus_conversion_time(mut self, conversion_time: ConversionTime) -> Self {
+        self.config.bus_conversion_time = conversion_time;
+        self
+    }
+
+    /// Sets the conversion time used for shunt voltage measurements
+    pub fn shunt_conversion_time(mut self, conversion_time: ConversionTime) -> Self {
+        self.config.shunt_conversion_time = conversion_time;
+        self
+    }
+
+    /// Sets the continuous, bus and shunt measurement bits from an operating mode
+    pub fn mode(mut self, mode: OperatingMode) -> Self {
+        let bits = mode.bits();
+        self.config.continuous = bits & CONTINUOUS_FLAG > 0;
+        self.config.bus_measurement = bits & BUS_MEASUREMENT_FLAG > 0;
+        self.config.shunt_measurement = bits & SHUNT_MEASUREMENT_FLAG > 0;
+        self
+    }
+
+    /// Sets whether measurements are performed continuously rather than once
+    pub fn continuous(mut self, continuous: bool) -> Self {
+        self.config.continuous = continuous;
+        self
+    }
+
+    /// Enables or disables bus voltage measurements
+    pub fn bus_measurement(mut self, enabled: bool) -> Self {
+        self.config.bus_measurement = enabled;
+        self
+    }
+
+    /// Enables or disables shunt voltage measurements
+    pub fn shunt_measurement(mut self, enabled: bool) -> Self {
+        self.config.shunt_measurement = enabled;
+        self
+    }
+
+    /// Builds the configuration
+    pub fn build(self) -> Configuration {
+        self.config
+    }
+}
+
+impl Default for ConfigurationBuilder {
+    fn default() -> Self {
+        ConfigurationBuilder::new()
+    }
+}
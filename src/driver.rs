@@ -1,16 +1,16 @@
+use crate::common::{
+    self, AlertShadow, BurstRead, ConversionTimer, RegisterCache, Shunts, SnapshotBuilder,
+};
+use crate::config::{AVERAGING_SHIFT, BUS_CONVERSION_TIME_SHIFT, SHUNT_CONVERSION_TIME_SHIFT};
 use crate::registers::Register;
-use crate::{helpers, MaskEnableFlags, OperatingMode};
+use crate::{
+    Address, AlertStatus, Averaging, Channel, Configuration, ConversionTime, Error,
+    MaskEnableFlags, OperatingMode, Power, Snapshot,
+};
 use core::cell::RefCell;
+use hal::delay::DelayUs;
 use hal::i2c::I2c;
-use ohms::Voltage;
-
-const RESET_FLAG: u16 = 0x8000;
-const CHANNEL_1_FLAG: u16 = 0x4000;
-const CHANNEL_2_FLAG: u16 = 0x2000;
-const CHANNEL_3_FLAG: u16 = 0x1000;
-
-const SHUNT_VOLTAGE_SCALE_FACTOR: i32 = 40;
-const BUS_VOLTAGE_SCALE_FACTOR: i32 = 8000;
+use ohms::{Current, Resistance, Voltage};
 
 /// Device driver for the INA3221 current and power monitor
 ///
@@ -24,6 +24,12 @@ const BUS_VOLTAGE_SCALE_FACTOR: i32 = 8000;
 ///
 /// The INA3221 can be configured to use different operating modes and alert thresholds.
 ///
+/// The number of averaged samples and the conversion times of the shunt and bus voltage
+/// measurements can also be adjusted to trade measurement speed for lower noise.
+///
+/// The whole Configuration register can also be read and written at once as a [`Configuration`]
+/// using the `read_config()` and `write_config()` methods.
+///
 /// It is important to note that the INA3221 will retain configuration settings unless the device is
 /// reset or power cycled. You can manually reset the device by calling the `reset()` method.
 ///
@@ -39,8 +45,9 @@ const BUS_VOLTAGE_SCALE_FACTOR: i32 = 8000;
 /// The last measurements are retained in the device's registers, even if the channel
 /// is disabled. This means that the last measurements will be returned when the channel is read.
 ///
-/// Channel index is zero-based, so channel 1 is index 0, channel 2 is index 1, and channel 3 is index 2.
-/// Attempting to use a channel index outside of the range of 0-2 will use the third channel.
+/// Channels are selected using the [`Channel`] enum, and `Channel::ALL` can be used to iterate over
+/// all three channels. A zero-based index can be converted using `Channel::try_from(index)`,
+/// so channel 1 is index 0, channel 2 is index 1, and channel 3 is index 2.
 ///
 /// # Shunt Resistor
 ///
@@ -54,48 +61,52 @@ const BUS_VOLTAGE_SCALE_FACTOR: i32 = 8000;
 /// # Current Calculation
 ///
 /// Unlike the INA219, the INA3221 does not store the shunt resistor value in the device,
-/// and so the current draw must be calculated manually instead of using the device's built-in
+/// and so the current draw must be calculated in firmware instead of using the device's built-in
 /// current calculation and register.
 ///
 /// The bonus of this is that the shunt resistor value can be changed without the need to
 /// calibrate the INA3221, only the firmware needs to be updated.
 ///
-/// The current draw can be calculated using Ohm's Law:
+/// The shunt resistance of each channel can be configured on the driver using the
+/// `set_shunt_resistance()` method, after which the current draw is calculated using Ohm's Law:
 /// I = V / R
 ///
+/// The calculation uses integer math only, so it does not require an FPU.
+///
 /// The [`ohms`](https://github.com/UnderLogic/ohms) crate is re-exported by this crate,
 /// so you can use the defined unit types to make the calculation easier to read and keep
 /// track of the denominator units.
 ///
 /// ## Example
 ///
-/// ```rust
+/// ```rust,no_run
+/// # use ina3221::prelude::*;
+/// # let mut i2c = ina3221::record::Replay::new("");
+/// # let mut ina = INA3221::new(&mut i2c, Address::Gnd);
 /// // Assume a shunt resistor value of 0.1 ohms
-/// let shunt_resistor = 100u32.milli_ohms();
-/// let shunt_voltage = ina.get_shunt_voltage(0).unwrap();
-/// let current_milliamps = shunt_voltage.milli_volts() / shunt_resistor.ohms();
+/// ina.set_shunt_resistance(Channel::Ch1, 100u32.milli_ohms());
+/// let current = ina.get_current(Channel::Ch1).unwrap();
 /// ```
 ///
 /// # Power Calculation
 ///
-/// Similar to the current calculation, the power draw can be calculated using Ohm's Law:
+/// Similar to the current calculation, the power draw is calculated using Ohm's Law:
 /// P = I * V
 ///
-/// Again, it is important to be mindful of the units used when calculating the power draw.
+/// The load voltage is the sum of the bus and shunt voltages, which is also available
+/// using the `get_load_voltage()` method.
 ///
 /// ## Example
 ///
-/// ```rust
+/// ```rust,no_run
+/// # use ina3221::prelude::*;
+/// # let mut i2c = ina3221::record::Replay::new("");
+/// # let mut ina = INA3221::new(&mut i2c, Address::Gnd);
 /// // Assume a shunt resistor value of 0.1 ohms
-/// let shunt_resistor = 100u32.milli_ohms();
-/// let shunt_voltage = ina.get_shunt_voltage(0).unwrap();
-/// let bus_voltage = ina.get_bus_voltage(0).unwrap();
-///
-/// // Can use the '+' operator to add the shunt and bus voltage structs together
-/// let load_voltage = bus_voltage + shunt_voltage;
+/// ina.set_shunt_resistance(Channel::Ch1, 100u32.milli_ohms());
 ///
-/// let current_milliamps = shunt_voltage.milli_volts() / shunt_resistor.ohms();
-/// let power_milliwatts = current_milliamps * load_voltage.volts();
+/// let load_voltage = ina.get_load_voltage(Channel::Ch1).unwrap();
+/// let power = ina.get_power(Channel::Ch1).unwrap();
 /// ```
 ///
 /// # Operating Mode
@@ -106,6 +117,9 @@ const BUS_VOLTAGE_SCALE_FACTOR: i32 = 8000;
 /// - Triggered
 /// - Continuous
 ///
+/// Triggered and continuous modes can each measure the shunt voltage, the bus voltage, or both,
+/// as selected by the [`Measurement`](crate::Measurement) of the mode.
+///
 /// The default operating mode is continuous, which means that the device will continuously
 /// measure the shunt and bus voltages and store the results in the device's registers.
 /// Any disabled channels will not be measured and are skipped from the measurement cycle.
@@ -114,6 +128,29 @@ const BUS_VOLTAGE_SCALE_FACTOR: i32 = 8000;
 /// voltages, and then enter a power-down state. The device will remain in this state until
 /// the operating mode is changed to either continuous or triggered (again).
 ///
+/// The `measure_once()` method triggers a measurement and waits for it to complete, which is
+/// useful for taking occasional measurements while keeping the device powered down in between.
+///
+/// ```rust,no_run
+/// # use ina3221::prelude::*;
+/// # let mut i2c = ina3221::record::Replay::new("");
+/// # let mut ina = INA3221::new(&mut i2c, Address::Gnd);
+/// # struct Delay;
+/// # impl Delay {
+/// #     fn new() -> Delay {
+/// #         Delay
+/// #     }
+/// # }
+/// # impl embedded_hal::delay::DelayUs for Delay {
+/// #     type Error = core::convert::Infallible;
+/// #     fn delay_us(&mut self, _us: u32) -> Result<(), Self::Error> {
+/// #         Ok(())
+/// #     }
+/// # }
+/// let mut delay = Delay::new(/* initialize your delay/clocks */);
+/// let snapshot = ina.measure_once(&mut delay).unwrap();
+/// ```
+///
 /// The power-down mode will disable all measurements and put the device into a low-power state.
 /// The last measurement results will be stored in the device's registers and can be read even
 /// while powered down.
@@ -134,25 +171,158 @@ const BUS_VOLTAGE_SCALE_FACTOR: i32 = 8000;
 ///
 /// # Example
 ///
-/// ```rust
+/// ```rust,no_run
+/// # use ina3221::prelude::*;
+/// # let mut i2c = ina3221::record::Replay::new("");
+/// # let mut ina = INA3221::new(&mut i2c, Address::Gnd);
 /// let max_current = 1u32.amps();  // 1A
-/// let shunt_resistor = 100u32.ohms(); // 0.1 ohms
+/// let shunt_resistor = 100u32.milli_ohms(); // 0.1 ohms
 ///
-/// // Calculate the maximum voltage that can be measured on the shunt using Ohm's Law (V = I * R)
-/// let max_voltage = max_current.milli_amps() * shunt_resistor.ohms(); // 100mV
+/// // The maximum shunt voltage is 1A * 0.1 ohms using Ohm's Law (V = I * R)
+/// let max_voltage = 100u32.milli_volts(); // 100mV
 ///
 /// // Set the critical alert limit for channel 1 to raise when exceeding 1A of current draw
-/// ina.set_critical_alert_limit(0, max_voltage.milli_volts()).unwrap();
+/// ina.set_critical_alert_limit(Channel::Ch1, max_voltage).unwrap();
+///
+/// // Or let the driver do the calculation using the configured shunt resistance
+/// ina.set_shunt_resistance(Channel::Ch1, shunt_resistor);
+/// ina.set_critical_current_limit(Channel::Ch1, max_current).unwrap();
 /// ```
 ///
 /// Note that these limits are based on the shunt voltage, **not** the load voltage.
+/// Limits that cannot be represented by the limit registers will return `Error::LimitOutOfRange`,
+/// the shunt voltage range of the limit registers is roughly ±163.8mV.
+///
+/// The raised alerts can be read using `read_alert_status()`, which separates the alert status
+/// from the latch and summation control flags of the Mask/Enable register.
+///
+/// ```rust,no_run
+/// # use ina3221::prelude::*;
+/// # let mut i2c = ina3221::record::Replay::new("");
+/// # let mut ina = INA3221::new(&mut i2c, Address::Gnd);
+/// let status = ina.read_alert_status().unwrap();
+/// for (channel, kind) in status.iter() {
+///     // Channel exceeded its critical or warning alert limit
+/// }
+/// ```
+///
+/// # Summation
+///
+/// The shunt voltages of any combination of channels can be summed together by the INA3221,
+/// which is useful when a load is shared across multiple parallel shunts.
+/// The summed shunt voltage is compared against the summation alert limit, which raises the
+/// summation alert and the critical alert pin when exceeded.
+///
+/// ```rust,no_run
+/// # use ina3221::prelude::*;
+/// # let mut i2c = ina3221::record::Replay::new("");
+/// # let mut ina = INA3221::new(&mut i2c, Address::Gnd);
+/// // Sum all three channels and alert if the total exceeds 150mV of shunt voltage
+/// for channel in Channel::ALL {
+///     ina.set_summation_channel_enabled(channel, true).unwrap();
+/// }
+///
+/// ina.set_summation_alert_limit(150u32.milli_volts()).unwrap();
+/// let total_voltage = ina.get_shunt_voltage_sum().unwrap();
+/// ```
+///
+/// # Register Cache
+///
+/// Every setter reads the register before writing it, which can add a lot of traffic to a shared
+/// I2C bus. The driver can optionally keep a copy of the configuration and limit registers, so that
+/// getters are served from the cache and setters only write the register.
+///
+/// The cache is only updated by the driver, so `sync()` or `invalidate()` should be called
+/// whenever the INA3221 is changed by anything else, such as a power cycle.
+///
+/// ```rust,no_run
+/// # use ina3221::prelude::*;
+/// # let mut i2c = ina3221::record::Replay::new("");
+/// # let mut ina = INA3221::new(&mut i2c, Address::Gnd);
+/// ina.set_cache_enabled(true);
+/// ina.sync().unwrap();
+///
+/// // Only writes the Configuration register
+/// ina.set_mode(OperatingMode::PowerDown).unwrap();
+/// ```
+///
+/// # Burst Reads
+///
+/// Each register is read in its own I2C transaction, using a repeated start between setting the
+/// register pointer and reading the register. With burst reads enabled, `read_all()` instead
+/// reads the shunt and bus voltages of every channel in a single transaction, so another
+/// controller cannot access the INA3221 part way through a snapshot.
+///
+/// The INA3221 does not auto-increment the register pointer, so the pointer is still written
+/// before every register within the transaction. All six measurement registers are read even if
+/// some channels are disabled, and no transaction is made if all channels are disabled.
+///
+/// There is no fallback to reading each register separately. If the burst transaction fails,
+/// `read_all()` returns the I2C error, as retrying register by register would give up the
+/// guarantee that the snapshot was read without interruption.
+///
+/// ```rust,no_run
+/// # use ina3221::prelude::*;
+/// # let mut i2c = ina3221::record::Replay::new("");
+/// # let mut ina = INA3221::new(&mut i2c, Address::Gnd);
+/// ina.set_burst_enabled(true);
+/// let snapshot = ina.read_all().unwrap();
+/// ```
+///
+/// # Sharing the I2C Bus
+///
+/// The driver takes ownership of the I2C peripheral, which can be released again using
+/// `destroy()`. To keep using the bus while the driver exists, pass a mutable reference instead,
+/// as `&mut I2C` also implements `I2c`:
+///
+/// ```rust,no_run
+/// # use ina3221::prelude::*;
+/// # let mut i2c = ina3221::record::Replay::new("");
+/// let mut ina = INA3221::new(&mut i2c, Address::Gnd);
+/// let voltage = ina.get_bus_voltage(Channel::Ch1).unwrap();
+/// drop(ina);
+/// ```
+///
+/// To share one peripheral between several INA3221s and other sensors at the same time,
+/// wrap it in one of the shared bus devices of [`embedded-hal-bus`], such as `RefCellDevice`
+/// for single-threaded use, or `CriticalSectionDevice` when the bus is also used from interrupts.
+/// These examples need `embedded-hal-bus` (and `critical-section` for the latter) added to your
+/// own dependencies, using a release built on the same `embedded-hal` version as this crate:
+///
+/// ```rust,ignore
+/// use core::cell::RefCell;
+/// use embedded_hal_bus::i2c::RefCellDevice;
+///
+/// let i2c = RefCell::new(i2c);
+/// let mut ina1 = INA3221::new(RefCellDevice::new(&i2c), Address::Gnd);
+/// let mut ina2 = INA3221::new(RefCellDevice::new(&i2c), Address::Vs);
+/// ```
+///
+/// ```rust,ignore
+/// use core::cell::RefCell;
+/// use critical_section::Mutex;
+/// use embedded_hal_bus::i2c::CriticalSectionDevice;
+///
+/// let i2c = Mutex::new(RefCell::new(i2c));
+/// let mut ina = INA3221::new(CriticalSectionDevice::new(&i2c), Address::Gnd);
+/// ```
+///
+/// [`embedded-hal-bus`]: https://docs.rs/embedded-hal-bus/
+///
+/// # Errors
+///
+/// All methods return an [`Error`], which wraps any error from the I2C bus as `Error::I2c`
+/// and reports invalid arguments as distinct variants, instead of silently doing the wrong thing.
 ///
 /// [INA3221]: https://www.ti.com/lit/ds/symlink/ina3221.pdf
 ///
 pub struct INA3221<I2C> {
     i2c: RefCell<I2C>,
-    /// I2C address of the INA3221
-    pub address: u8,
+    address: Address,
+    shunts: Shunts,
+    alerts: AlertShadow,
+    cache: RegisterCache,
+    burst: bool,
 }
 
 impl<I2C, E> INA3221<I2C>
@@ -161,54 +331,138 @@ where
 {
     /// Create a new INA3221 driver instance from an I2C peripheral on a specific address
     ///
-    /// This is typically 0x40, 0x41, or 0x42 depending on the A0 pin setting
-    pub fn new(i2c: I2C, address: u8) -> INA3221<I2C> {
+    /// The address is selected by tying the A0 pin to GND, VS, SDA, or SCL
+    pub fn new(i2c: I2C, address: Address) -> INA3221<I2C> {
         INA3221 {
             i2c: RefCell::new(i2c),
             address,
+            shunts: Shunts::default(),
+            alerts: AlertShadow::default(),
+            cache: RegisterCache::default(),
+            burst: false,
         }
     }
 
+    /// Create a new INA3221 driver instance, verifying that the device is a genuine INA3221
+    ///
+    /// Returns `Error::UnexpectedDevice` if the manufacturer ID is not 0x5449 or the die ID is not
+    /// 0x3220, such as when an INA219 answers at the same address.
+    ///
+    /// The I2C peripheral is dropped if the device cannot be verified, so pass `&mut I2C` instead
+    /// to keep using the bus afterwards
+    pub fn probe(i2c: I2C, address: Address) -> Result<INA3221<I2C>, Error<E>> {
+        let ina = INA3221::new(i2c, address);
+        ina.verify_identity()?;
+        Ok(ina)
+    }
+
+    /// Create a new INA3221 driver instance like `probe()`, then reset the device
+    ///
+    /// This guarantees that the device starts from the power-on default configuration
+    /// regardless of how it was left by previous firmware
+    pub fn probe_and_reset(i2c: I2C, address: Address) -> Result<INA3221<I2C>, Error<E>> {
+        let mut ina = INA3221::probe(i2c, address)?;
+        ina.reset()?;
+        Ok(ina)
+    }
+
+    /// Destroys the driver instance, returning the I2C peripheral
+    pub fn destroy(self) -> I2C {
+        self.i2c.into_inner()
+    }
+
+    /// Gets the I2C address of the INA3221
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
     /// Gets the active configuration bits from the INA3221
-    pub fn get_configuration(&self) -> Result<u16, E> {
+    pub fn get_configuration(&self) -> Result<u16, Error<E>> {
         self.read_register(Register::Configuration)
     }
 
-    /// Gets the operating mode of the INA3221
-    pub fn get_mode(&self) -> Result<OperatingMode, E> {
+    /// Reads the Configuration register from the INA3221
+    pub fn read_config(&self) -> Result<Configuration, Error<E>> {
         let config = self.get_configuration()?;
-        let mode = match config & 0x7 {
-            0x01 => OperatingMode::Triggered,
-            0x02 => OperatingMode::Triggered,
-            0x03 => OperatingMode::Triggered,
-            0x05 => OperatingMode::Continuous,
-            0x06 => OperatingMode::Continuous,
-            0x07 => OperatingMode::Continuous,
-            _ => OperatingMode::PowerDown,
-        };
+        Ok(Configuration::from_bits(config))
+    }
 
-        Ok(mode)
+    /// Writes the Configuration register of the INA3221 in a single transaction
+    ///
+    /// This is the exact inverse of `read_config()`
+    pub fn write_config(&mut self, config: &Configuration) -> Result<(), Error<E>> {
+        self.write_register(Register::Configuration, config.bits())
+    }
+
+    /// Gets the operating mode of the INA3221
+    pub fn get_mode(&self) -> Result<OperatingMode, Error<E>> {
+        let config = self.get_configuration()?;
+        Ok(OperatingMode::from_bits(config))
     }
 
     /// Sets the operating mode of the INA3221
     ///
     /// Setting the mode to `OperatingMode::Triggered` will trigger a measurement cycle
-    pub fn set_mode(&mut self, mode: OperatingMode) -> Result<(), E> {
+    pub fn set_mode(&mut self, mode: OperatingMode) -> Result<(), Error<E>> {
         let config = self.get_configuration()?;
-        let new_config = (config & 0xFFF8) | mode as u16;
+        let new_config = common::update_mode(config, mode);
         self.write_register(Register::Configuration, new_config)
     }
 
+    /// Gets the number of samples averaged together for each measurement
+    pub fn get_averaging(&self) -> Result<Averaging, Error<E>> {
+        let config = self.get_configuration()?;
+        Ok(Averaging::from_bits(config >> AVERAGING_SHIFT))
+    }
+
+    /// Sets the number of samples averaged together for each measurement
+    ///
+    /// Averaging applies to both the shunt and bus voltage measurements of all channels
+    pub fn set_averaging(&mut self, averaging: Averaging) -> Result<(), Error<E>> {
+        self.set_config_field(AVERAGING_SHIFT, averaging as u16)
+    }
+
+    /// Gets the conversion time used for bus voltage measurements
+    pub fn get_bus_conversion_time(&self) -> Result<ConversionTime, Error<E>> {
+        let config = self.get_configuration()?;
+        Ok(ConversionTime::from_bits(
+            config >> BUS_CONVERSION_TIME_SHIFT,
+        ))
+    }
+
+    /// Sets the conversion time used for bus voltage measurements
+    pub fn set_bus_conversion_time(
+        &mut self,
+        conversion_time: ConversionTime,
+    ) -> Result<(), Error<E>> {
+        self.set_config_field(BUS_CONVERSION_TIME_SHIFT, conversion_time as u16)
+    }
+
+    /// Gets the conversion time used for shunt voltage measurements
+    pub fn get_shunt_conversion_time(&self) -> Result<ConversionTime, Error<E>> {
+        let config = self.get_configuration()?;
+        Ok(ConversionTime::from_bits(
+            config >> SHUNT_CONVERSION_TIME_SHIFT,
+        ))
+    }
+
+    /// Sets the conversion time used for shunt voltage measurements
+    pub fn set_shunt_conversion_time(
+        &mut self,
+        conversion_time: ConversionTime,
+    ) -> Result<(), Error<E>> {
+        self.set_config_field(SHUNT_CONVERSION_TIME_SHIFT, conversion_time as u16)
+    }
+
     /// Gets the enabled status for all three channels, storing them in an array
     ///
     /// This is useful for iterating over all channels without having to call
     /// `is_channel_enabled` multiple times
-    pub fn get_channels_enabled(&self, statuses: &mut [bool]) -> Result<(), E> {
+    ///
+    /// Returns `Error::InvalidChannel` if `statuses` has fewer than three elements
+    pub fn get_channels_enabled(&self, statuses: &mut [bool]) -> Result<(), Error<E>> {
         let config = self.get_configuration()?;
-        statuses[0] = config & CHANNEL_1_FLAG > 0;
-        statuses[1] = config & CHANNEL_2_FLAG > 0;
-        statuses[2] = config & CHANNEL_3_FLAG > 0;
-        Ok(())
+        common::decode_channels_enabled(config, statuses)
     }
 
     /// Sets the enabled status for all three channels
@@ -218,31 +472,19 @@ where
     ///
     /// Disabling a channel prevents it from being measured, but it can still be read
     /// for the last measurement result
-    pub fn set_channels_enabled(&mut self, enabled: &[bool]) -> Result<(), E> {
+    ///
+    /// Returns `Error::InvalidChannel` if `enabled` has fewer than three elements
+    pub fn set_channels_enabled(&mut self, enabled: &[bool]) -> Result<(), Error<E>> {
         let config = self.get_configuration()?;
-        let mut new_config = config & 0xFFF8;
-        if enabled[0] {
-            new_config |= CHANNEL_1_FLAG;
-        }
-        if enabled[1] {
-            new_config |= CHANNEL_2_FLAG;
-        }
-        if enabled[2] {
-            new_config |= CHANNEL_3_FLAG;
-        }
+        let new_config = common::update_channels_enabled(config, enabled)?;
         self.write_register(Register::Configuration, new_config)
     }
 
     /// Checks if a monitoring channel is enabled on the INA3221
     ///
     /// A disabled channel can still be read, but will not be measured until it is re-enabled
-    pub fn is_channel_enabled(&self, channel: u8) -> Result<bool, E> {
-        let flag = match channel {
-            0 => CHANNEL_1_FLAG,
-            1 => CHANNEL_2_FLAG,
-            _ => CHANNEL_3_FLAG,
-        };
-
+    pub fn is_channel_enabled(&self, channel: Channel) -> Result<bool, Error<E>> {
+        let flag = channel.enable_flag();
         let config = self.get_configuration()?;
         Ok(config & flag > 0)
     }
@@ -251,64 +493,156 @@ where
     ///
     /// Disabling a channel prevents it from being measured, but it can still be read
     /// for the last measurement result
-    pub fn set_channel_enabled(&mut self, channel: u8, enabled: bool) -> Result<(), E> {
-        let flag = match channel {
-            0 => CHANNEL_1_FLAG,
-            1 => CHANNEL_2_FLAG,
-            _ => CHANNEL_3_FLAG,
-        };
+    pub fn set_channel_enabled(&mut self, channel: Channel, enabled: bool) -> Result<(), Error<E>> {
+        let config = self.get_configuration()?;
+        let new_config = common::update_channel_enabled(config, channel, enabled);
+        self.write_register(Register::Configuration, new_config)
+    }
+
+    /// Gets the shunt voltage of a specific monitoring channel
+    pub fn get_shunt_voltage(&self, channel: Channel) -> Result<Voltage, Error<E>> {
+        let raw_value = self.read_register(channel.shunt_voltage_register())?;
+        Ok(common::decode_shunt_voltage(raw_value))
+    }
+
+    /// Gets the bus voltage of a specific monitoring channel
+    pub fn get_bus_voltage(&self, channel: Channel) -> Result<Voltage, Error<E>> {
+        let raw_value = self.read_register(channel.bus_voltage_register())?;
+        Ok(common::decode_bus_voltage(raw_value))
+    }
+
+    /// Gets the configured shunt resistance of a specific monitoring channel
+    pub fn get_shunt_resistance(&self, channel: Channel) -> Option<Resistance> {
+        self.shunts.get(channel)
+    }
+
+    /// Sets the shunt resistance of a specific monitoring channel
+    ///
+    /// This is only stored in the driver and is used to calculate the current and power
+    /// of the channel, a resistance of zero is treated as not configured
+    pub fn set_shunt_resistance(&mut self, channel: Channel, resistance: Resistance) {
+        self.shunts.set(channel, resistance);
+    }
 
+    /// Gets the load voltage of a specific monitoring channel
+    ///
+    /// This is the sum of the bus and shunt voltages of that channel
+    pub fn get_load_voltage(&self, channel: Channel) -> Result<Voltage, Error<E>> {
+        let shunt_voltage = self.get_shunt_voltage(channel)?;
+        let bus_voltage = self.get_bus_voltage(channel)?;
+        Ok(bus_voltage + shunt_voltage)
+    }
+
+    /// Gets the current draw of a specific monitoring channel
+    ///
+    /// Returns `Error::MissingShuntResistance` if no shunt resistance is configured for that channel
+    pub fn get_current(&self, channel: Channel) -> Result<Current, Error<E>> {
+        self.shunts.milli_ohms(channel)?;
+        let shunt_voltage = self.get_shunt_voltage(channel)?;
+        self.shunts.current(channel, shunt_voltage)
+    }
+
+    /// Gets the power draw of a specific monitoring channel
+    ///
+    /// Returns `Error::MissingShuntResistance` if no shunt resistance is configured for that channel
+    pub fn get_power(&self, channel: Channel) -> Result<Power, Error<E>> {
+        self.shunts.milli_ohms(channel)?;
+        let shunt_voltage = self.get_shunt_voltage(channel)?;
+        let bus_voltage = self.get_bus_voltage(channel)?;
+        self.shunts.power(channel, shunt_voltage, bus_voltage)
+    }
+
+    /// Reads the measurements of all enabled monitoring channels
+    ///
+    /// Disabled channels are skipped, and the current and power are only included
+    /// for channels with a configured shunt resistance
+    pub fn read_all(&self) -> Result<Snapshot, Error<E>> {
         let config = self.get_configuration()?;
+        let mut snapshot = SnapshotBuilder::new(config);
 
-        // Toggle the channel bit in the configuration
-        match enabled {
-            true => self.write_register(Register::Configuration, config | flag),
-            false => self.write_register(Register::Configuration, config & !flag),
+        let burst = if self.burst && snapshot.has_enabled() {
+            Some(self.read_burst()?)
+        } else {
+            None
+        };
+
+        for channel in Channel::ALL {
+            if !snapshot.is_enabled(channel) {
+                continue;
+            }
+
+            let (shunt_voltage, bus_voltage) = match &burst {
+                Some(burst) => burst.voltages(channel),
+                None => (
+                    self.get_shunt_voltage(channel)?,
+                    self.get_bus_voltage(channel)?,
+                ),
+            };
+            snapshot.set(
+                channel,
+                self.shunts.reading(channel, shunt_voltage, bus_voltage),
+            );
         }
+
+        Ok(snapshot.build())
     }
 
-    /// Gets the shunt voltage of a specific monitoring channel
-    pub fn get_shunt_voltage(&self, channel: u8) -> Result<Voltage, E> {
-        let register = match channel {
-            0 => Register::ShuntVoltage1,
-            1 => Register::ShuntVoltage2,
-            _ => Register::ShuntVoltage3,
-        };
+    /// Reads the measurements of all enabled monitoring channels once a conversion is ready
+    ///
+    /// Returns `None` if the conversion ready flag is not set, otherwise the measurements are read
+    /// with `read_all()`. In continuous mode the INA3221 keeps converting while they are read, so
+    /// the channels may come from different cycles. Use `measure_once()` if they must match.
+    ///
+    /// Any alert flags read while checking for the conversion are kept and returned by the
+    /// next call to `read_alert_flags()`
+    pub fn read_all_if_ready(&self) -> Result<Option<Snapshot>, Error<E>> {
+        let flags = self.read_mask_enable()?;
+        if !flags.contains(MaskEnableFlags::CONVERSION_READY) {
+            return Ok(None);
+        }
 
-        // LSB = 40uV, meaning the value is downscaled 40:1
-        let raw_value = self.read_register(register)?;
-        let microvolts = helpers::convert_from_12bit_signed(raw_value) * SHUNT_VOLTAGE_SCALE_FACTOR;
-        Ok(Voltage::from_micro_volts(microvolts))
+        self.read_all().map(Some)
     }
 
-    /// Gets the bus voltage of a specific monitoring channel
-    pub fn get_bus_voltage(&self, channel: u8) -> Result<Voltage, E> {
-        let register = match channel {
-            0 => Register::BusVoltage1,
-            1 => Register::BusVoltage2,
-            _ => Register::BusVoltage3,
-        };
+    /// Triggers a single measurement and waits for it to complete
+    ///
+    /// The currently selected measurements are kept, or both shunt and bus voltages are measured
+    /// if the device is powered down. The conversion ready flag is polled until the measurement
+    /// completes, returning `Error::Timeout` if it takes more than twice the expected time
+    /// based on the configured averaging and conversion times. Returns `Error::NoChannelsEnabled`
+    /// without triggering a measurement if every channel is disabled, or `Error::Delay` if the
+    /// delay between polls fails.
+    ///
+    /// Any alert flags read while polling are kept and returned by the next call to
+    /// `read_alert_flags()`. The device powers down once the measurement completes.
+    pub fn measure_once<D: DelayUs>(&mut self, delay: &mut D) -> Result<Snapshot, Error<E>> {
+        // Writing the configuration triggers the measurement and clears the conversion ready flag
+        let config = common::trigger_config(self.read_config()?);
+        let mut timer = ConversionTimer::new(&config)?;
+        self.write_config(&config)?;
+
+        delay
+            .delay_us(timer.initial_delay())
+            .map_err(|_| Error::Delay)?;
+
+        while !self
+            .read_mask_enable()?
+            .contains(MaskEnableFlags::CONVERSION_READY)
+        {
+            delay
+                .delay_us(timer.next_delay()?)
+                .map_err(|_| Error::Delay)?;
+        }
 
-        // LSB = 8mV (8000uV), meaning the value is downscaled 8:1
-        let raw_value = self.read_register(register)?;
-        let microvolts = helpers::convert_from_12bit_signed(raw_value) * BUS_VOLTAGE_SCALE_FACTOR;
-        Ok(Voltage::from_micro_volts(microvolts))
+        self.read_all()
     }
 
     /// Gets the critical alert limit of a specific monitoring channel
     ///
     /// This is the shunt voltage limit that will trigger a critical alert on that channel
-    pub fn get_critical_alert_limit(&self, channel: u8) -> Result<Voltage, E> {
-        let register = match channel {
-            0 => Register::CriticalAlertLimit1,
-            1 => Register::CriticalAlertLimit2,
-            _ => Register::CriticalAlertLimit3,
-        };
-
-        // LSB = 40uV, meaning the value is downscaled 40:1
-        let raw_value = self.read_register(register)?;
-        let microvolts = helpers::convert_from_12bit_signed(raw_value) * SHUNT_VOLTAGE_SCALE_FACTOR;
-        Ok(Voltage::from_micro_volts(microvolts))
+    pub fn get_critical_alert_limit(&self, channel: Channel) -> Result<Voltage, Error<E>> {
+        let raw_value = self.read_register(channel.critical_alert_limit_register())?;
+        Ok(common::decode_shunt_voltage(raw_value))
     }
 
     /// Sets the critical alert limit for a specific monitoring channel
@@ -316,41 +650,48 @@ where
     /// This is the shunt voltage limit that will trigger a critical alert on that channel
     pub fn set_critical_alert_limit(
         &mut self,
-        channel: u8,
+        channel: Channel,
         voltage_limit: Voltage,
-    ) -> Result<(), E> {
-        let register = match channel {
-            0 => Register::CriticalAlertLimit1,
-            1 => Register::CriticalAlertLimit2,
-            _ => Register::CriticalAlertLimit3,
-        };
+    ) -> Result<(), Error<E>> {
+        let raw_value = common::encode_shunt_voltage(voltage_limit)?;
+        self.write_register(channel.critical_alert_limit_register(), raw_value)
+    }
 
-        // LSB = 40uV, meaning the value is downscaled 40:1
-        let raw_value = voltage_limit.micro_volts() / SHUNT_VOLTAGE_SCALE_FACTOR;
-        self.write_register(register, helpers::convert_to_12bit_signed(raw_value))
+    /// Gets the critical alert limit of a specific monitoring channel as a current
+    ///
+    /// Returns `Error::MissingShuntResistance` if no shunt resistance is configured for that channel
+    pub fn get_critical_current_limit(&self, channel: Channel) -> Result<Current, Error<E>> {
+        self.shunts.milli_ohms(channel)?;
+        let voltage_limit = self.get_critical_alert_limit(channel)?;
+        self.shunts.current(channel, voltage_limit)
+    }
+
+    /// Sets the critical alert limit for a specific monitoring channel as a current
+    ///
+    /// The current is converted to a shunt voltage limit using the configured shunt resistance.
+    /// Returns `Error::MissingShuntResistance` if no shunt resistance is configured for that channel
+    pub fn set_critical_current_limit(
+        &mut self,
+        channel: Channel,
+        current_limit: Current,
+    ) -> Result<(), Error<E>> {
+        let voltage_limit = self.shunts.shunt_voltage(channel, current_limit)?;
+        self.set_critical_alert_limit(channel, voltage_limit)
     }
 
     /// Sets the critical alert latch behavior for the warning alert pin
     ///
     /// If enabled, the critical alert pin will latch until the warning alert is cleared
-    pub fn set_critical_alert_latch(&mut self, enabled: bool) -> Result<(), E> {
+    pub fn set_critical_alert_latch(&mut self, enabled: bool) -> Result<(), Error<E>> {
         self.set_flag(MaskEnableFlags::CRITICAL_ALERT_LATCH, enabled)
     }
 
     /// Gets the warning alert limit of a specific monitoring channel
     ///
     /// This is the shunt voltage limit that will trigger a warning alert on that channel
-    pub fn get_warning_alert_limit(&self, channel: u8) -> Result<Voltage, E> {
-        let register = match channel {
-            0 => Register::WarningAlertLimit1,
-            1 => Register::WarningAlertLimit2,
-            _ => Register::WarningAlertLimit3,
-        };
-
-        // LSB = 40uV, meaning the value is downscaled 40:1
-        let raw_value = self.read_register(register)?;
-        let microvolts = helpers::convert_from_12bit_signed(raw_value) * SHUNT_VOLTAGE_SCALE_FACTOR;
-        Ok(Voltage::from_micro_volts(microvolts))
+    pub fn get_warning_alert_limit(&self, channel: Channel) -> Result<Voltage, Error<E>> {
+        let raw_value = self.read_register(channel.warning_alert_limit_register())?;
+        Ok(common::decode_shunt_voltage(raw_value))
     }
 
     /// Sets the warning alert limit for a specific monitoring channel
@@ -358,44 +699,93 @@ where
     /// This is the shunt voltage limit that will trigger a warning alert on that channel
     pub fn set_warning_alert_limit(
         &mut self,
-        channel: u8,
+        channel: Channel,
         voltage_limit: Voltage,
-    ) -> Result<(), E> {
-        let register = match channel {
-            0 => Register::WarningAlertLimit1,
-            1 => Register::WarningAlertLimit2,
-            _ => Register::WarningAlertLimit3,
-        };
+    ) -> Result<(), Error<E>> {
+        let raw_value = common::encode_shunt_voltage(voltage_limit)?;
+        self.write_register(channel.warning_alert_limit_register(), raw_value)
+    }
+
+    /// Gets the warning alert limit of a specific monitoring channel as a current
+    ///
+    /// Returns `Error::MissingShuntResistance` if no shunt resistance is configured for that channel
+    pub fn get_warning_current_limit(&self, channel: Channel) -> Result<Current, Error<E>> {
+        self.shunts.milli_ohms(channel)?;
+        let voltage_limit = self.get_warning_alert_limit(channel)?;
+        self.shunts.current(channel, voltage_limit)
+    }
 
-        // LSB = 40uV, meaning the value is downscaled 40:1
-        let raw_value = voltage_limit.micro_volts() / SHUNT_VOLTAGE_SCALE_FACTOR;
-        self.write_register(register, helpers::convert_to_12bit_signed(raw_value))
+    /// Sets the warning alert limit for a specific monitoring channel as a current
+    ///
+    /// The current is converted to a shunt voltage limit using the configured shunt resistance.
+    /// Returns `Error::MissingShuntResistance` if no shunt resistance is configured for that channel
+    pub fn set_warning_current_limit(
+        &mut self,
+        channel: Channel,
+        current_limit: Current,
+    ) -> Result<(), Error<E>> {
+        let voltage_limit = self.shunts.shunt_voltage(channel, current_limit)?;
+        self.set_warning_alert_limit(channel, voltage_limit)
     }
 
     /// Sets the warning alert latch behavior for the warning alert pin
     ///
     /// If enabled, the warning alert pin will latch until the warning alert is cleared
-    pub fn set_warning_alert_latch(&mut self, enabled: bool) -> Result<(), E> {
+    pub fn set_warning_alert_latch(&mut self, enabled: bool) -> Result<(), Error<E>> {
         self.set_flag(MaskEnableFlags::WARNING_ALERT_LATCH, enabled)
     }
 
+    /// Checks if a monitoring channel is included in the shunt voltage summation
+    pub fn is_summation_channel_enabled(&self, channel: Channel) -> Result<bool, Error<E>> {
+        let control = self.get_alert_control()?;
+        Ok(control.contains(channel.summation_control_flag()))
+    }
+
+    /// Includes or excludes a monitoring channel from the shunt voltage summation
+    ///
+    /// The summed shunt voltage of all included channels is compared against the summation
+    /// alert limit, which raises the summation alert and the critical alert pin when exceeded
+    pub fn set_summation_channel_enabled(
+        &mut self,
+        channel: Channel,
+        enabled: bool,
+    ) -> Result<(), Error<E>> {
+        self.set_flag(channel.summation_control_flag(), enabled)
+    }
+
+    /// Gets the sum of the shunt voltages of all channels included in the summation
+    pub fn get_shunt_voltage_sum(&self) -> Result<Voltage, Error<E>> {
+        let raw_value = self.read_register(Register::ShuntVoltageSum)?;
+        Ok(common::decode_shunt_voltage_sum(raw_value))
+    }
+
+    /// Gets the summation alert limit
+    ///
+    /// This is the summed shunt voltage limit that will trigger a summation alert
+    pub fn get_summation_alert_limit(&self) -> Result<Voltage, Error<E>> {
+        let raw_value = self.read_register(Register::ShuntVoltageSumLimit)?;
+        Ok(common::decode_shunt_voltage_sum(raw_value))
+    }
+
+    /// Sets the summation alert limit
+    ///
+    /// This is the summed shunt voltage limit that will trigger a summation alert
+    pub fn set_summation_alert_limit(&mut self, voltage_limit: Voltage) -> Result<(), Error<E>> {
+        let raw_value = common::encode_shunt_voltage_sum(voltage_limit)?;
+        self.write_register(Register::ShuntVoltageSumLimit, raw_value)
+    }
+
     /// Gets the power valid limits of **all** enabled monitoring channels
     ///
     /// These are the lower and upper limits (respectively) for the bus voltage that will trigger
     /// a power valid alert on all enabled channels
-    pub fn get_power_valid_limits(&self) -> Result<(Voltage, Voltage), E> {
-        // LSB = 8mV (8000uV), meaning the value is downscaled 8:1
+    pub fn get_power_valid_limits(&self) -> Result<(Voltage, Voltage), Error<E>> {
         let lower_raw_value = self.read_register(Register::PowerValidLowerLimit)?;
         let upper_raw_value = self.read_register(Register::PowerValidUpperLimit)?;
 
-        let lower_microvolts =
-            helpers::convert_from_12bit_signed(lower_raw_value) * BUS_VOLTAGE_SCALE_FACTOR;
-        let upper_microvolts =
-            helpers::convert_from_12bit_signed(upper_raw_value) * BUS_VOLTAGE_SCALE_FACTOR;
-
         Ok((
-            Voltage::from_micro_volts(lower_microvolts),
-            Voltage::from_micro_volts(upper_microvolts),
+            common::decode_bus_voltage(lower_raw_value),
+            common::decode_bus_voltage(upper_raw_value),
         ))
     }
 
@@ -407,92 +797,210 @@ where
         &mut self,
         lower_limit: Voltage,
         upper_limit: Voltage,
-    ) -> Result<(), E> {
-        // LSB = 8mV (8000uV), meaning the value is downscaled 8:1
-        let lower_raw_value = lower_limit.micro_volts() / BUS_VOLTAGE_SCALE_FACTOR;
-        let upper_raw_value = upper_limit.micro_volts() / BUS_VOLTAGE_SCALE_FACTOR;
+    ) -> Result<(), Error<E>> {
+        let lower_raw_value = common::encode_bus_voltage(lower_limit)?;
+        let upper_raw_value = common::encode_bus_voltage(upper_limit)?;
 
-        self.write_register(
-            Register::PowerValidLowerLimit,
-            helpers::convert_to_12bit_signed(lower_raw_value),
-        )?;
-
-        self.write_register(
-            Register::PowerValidUpperLimit,
-            helpers::convert_to_12bit_signed(upper_raw_value),
-        )?;
-
-        Ok(())
+        self.write_register(Register::PowerValidLowerLimit, lower_raw_value)?;
+        self.write_register(Register::PowerValidUpperLimit, upper_raw_value)
     }
 
     /// Reads the alert flags from the INA3221
     ///
-    /// If `preserve` is set to `true`, the flags will not be cleared after reading
-    pub fn read_alert_flags(&mut self, preserve: bool) -> Result<MaskEnableFlags, E> {
+    /// Reading clears the alert flags on the device, so the driver keeps them until acknowledged.
+    /// If `preserve` is set to `true`, the alert flags are kept and returned again by the next read,
+    /// otherwise they are acknowledged and cleared
+    pub fn read_alert_flags(&mut self, preserve: bool) -> Result<MaskEnableFlags, Error<E>> {
         self.read_flags(preserve)
     }
 
+    /// Reads the alert status from the INA3221 without acknowledging the alerts
+    ///
+    /// This reports the same alerts as `read_alert_flags(true)`, but without the latch
+    /// and summation control flags
+    pub fn read_alert_status(&mut self) -> Result<AlertStatus, Error<E>> {
+        let flags = self.read_flags(true)?;
+        Ok(AlertStatus::from(flags))
+    }
+
+    /// Reads and acknowledges the alert status from the INA3221
+    ///
+    /// The returned alerts are cleared, so they are only reported again if raised by a later
+    /// conversion
+    pub fn acknowledge_alerts(&mut self) -> Result<AlertStatus, Error<E>> {
+        let flags = self.read_flags(false)?;
+        Ok(AlertStatus::from(flags))
+    }
+
+    /// Gets the latch and summation control flags
+    ///
+    /// These are served from the driver's copy of the Mask/Enable register, which is only read
+    /// the first time if the flags are not known yet. Any alerts cleared by that read are kept
+    /// until acknowledged
+    pub fn get_alert_control(&self) -> Result<MaskEnableFlags, Error<E>> {
+        match self.alerts.control() {
+            Some(control) => Ok(control),
+            None => Ok(self.read_mask_enable()? & MaskEnableFlags::CONTROL_FLAGS),
+        }
+    }
+
+    /// Sets the latch and summation control flags
+    ///
+    /// Any status flags in `control` are ignored, as they are read-only on the INA3221,
+    /// and any pending alerts are kept until acknowledged
+    pub fn set_alert_control(&mut self, control: MaskEnableFlags) -> Result<(), Error<E>> {
+        let control = control & MaskEnableFlags::CONTROL_FLAGS;
+        self.write_register(Register::MaskEnable, control.bits())?;
+        self.alerts.set_control(control);
+        Ok(())
+    }
+
     /// Gets the manufacturer ID from the INA3221
     ///
     /// This value is always 0x5449 ('TI' in ASCII), or at least should be for genuine INA3221s
-    pub fn get_manufacturer_id(&self) -> Result<u16, E> {
+    pub fn get_manufacturer_id(&self) -> Result<u16, Error<E>> {
         self.read_register(Register::ManufacturerId)
     }
 
     /// Gets the die ID from the INA3221
     ///
     /// This value is always 0x3220, or at least should be for genuine INA3221s
-    pub fn get_die_id(&self) -> Result<u16, E> {
+    pub fn get_die_id(&self) -> Result<u16, Error<E>> {
         self.read_register(Register::DieId)
     }
 
     /// Resets the INA3221
     ///
     /// This clears all configuration bits and sets the default configuration
-    pub fn reset(&mut self) -> Result<(), E> {
+    pub fn reset(&mut self) -> Result<(), Error<E>> {
         let config = self.read_register(Register::Configuration)?;
-        self.write_register(Register::Configuration, config | RESET_FLAG)
+        self.write_register(Register::Configuration, common::update_reset(config))
+    }
+
+    /// Checks if the register cache is enabled
+    pub fn is_cache_enabled(&self) -> bool {
+        self.cache.is_enabled()
+    }
+
+    /// Enables or disables the register cache
+    ///
+    /// When enabled, the configuration and limit registers are only read from the INA3221 once
+    /// and then served from the cache, so setters only need to write the register.
+    /// Enabling or disabling the cache discards any cached values
+    pub fn set_cache_enabled(&mut self, enabled: bool) {
+        self.cache.set_enabled(enabled);
     }
 
-    fn select_register(&self, register: Register) -> Result<(), E> {
-        self.i2c.borrow_mut().write(self.address, &[register as u8])
+    /// Reads the configuration, limit, and alert control registers into the cache
+    ///
+    /// This should be called after the INA3221 has been changed by something other than the
+    /// driver, such as a power cycle or another bus controller
+    pub fn sync(&mut self) -> Result<(), Error<E>> {
+        self.invalidate();
+
+        for register in RegisterCache::registers() {
+            self.read_register(register)?;
+        }
+
+        self.read_mask_enable()?;
+        Ok(())
+    }
+
+    /// Discards the cached registers and alert control flags, so they are read from the INA3221 the
+    /// next time they are used
+    pub fn invalidate(&mut self) {
+        self.cache.invalidate();
+        self.alerts.invalidate_control();
+    }
+
+    /// Checks if burst reads are enabled
+    pub fn is_burst_enabled(&self) -> bool {
+        self.burst
+    }
+
+    /// Enables or disables burst reads of the measurements
+    ///
+    /// When enabled, `read_all()` reads the shunt and bus voltages of every channel in a single
+    /// I2C transaction, so they cannot be interleaved with other traffic on the bus. If that
+    /// transaction fails, the error is returned without falling back to separate reads.
+    /// When disabled, each register is read in its own transaction
+    pub fn set_burst_enabled(&mut self, enabled: bool) {
+        self.burst = enabled;
+    }
+
+    fn verify_identity(&self) -> Result<(), Error<E>> {
+        let manufacturer_id = self.get_manufacturer_id()?;
+        let die_id = self.get_die_id()?;
+        common::verify_identity(manufacturer_id, die_id)
     }
 
-    fn read_register(&self, register: Register) -> Result<u16, E> {
-        self.select_register(register)?;
+    fn read_register(&self, register: Register) -> Result<u16, Error<E>> {
+        if let Some(value) = self.cache.get(register) {
+            return Ok(value);
+        }
 
+        // Use a repeated start, so the pointer cannot be moved by another controller in between
         let mut buffer: [u8; 2] = [0x00; 2];
-        self.i2c.borrow_mut().read(self.address, &mut buffer)?;
+        self.i2c
+            .borrow_mut()
+            .write_read(self.address.bits(), &[register as u8], &mut buffer)
+            .map_err(Error::I2c)?;
+
+        let value = common::decode_register_read(buffer);
+        self.cache.store(register, value);
+        Ok(value)
+    }
+
+    fn read_burst(&self) -> Result<BurstRead, Error<E>> {
+        let mut burst = BurstRead::new();
+        self.i2c
+            .borrow_mut()
+            .transaction(self.address.bits(), &mut burst.operations())
+            .map_err(Error::I2c)?;
 
-        // Convert from big endian 16-bit word
-        let word = ((buffer[0] as u16) << 8) + buffer[1] as u16;
-        Ok(word)
+        Ok(burst)
     }
 
-    fn write_register(&mut self, register: Register, value: u16) -> Result<(), E> {
-        // Convert from little endian to big endian
-        let msb = ((value >> 8) & 0xFF) as u8;
-        let lsb = (value & 0xFF) as u8;
+    fn write_register(&mut self, register: Register, value: u16) -> Result<(), Error<E>> {
+        let buffer = common::encode_register_write(register, value);
+        self.i2c
+            .borrow_mut()
+            .write(self.address.bits(), &buffer)
+            .map_err(Error::I2c)?;
+
+        self.cache.store(register, value);
+        if common::is_reset(register, value) {
+            // The reset also clears the latch and summation control flags
+            self.alerts.set_control(MaskEnableFlags::empty());
+        }
 
-        let buffer: [u8; 3] = [register as u8, msb, lsb];
-        self.i2c.borrow_mut().write(self.address, &buffer)?;
         Ok(())
     }
 
-    fn read_flags(&mut self, preserve: bool) -> Result<MaskEnableFlags, E> {
+    fn set_config_field(&mut self, shift: u16, value: u16) -> Result<(), Error<E>> {
+        let config = self.get_configuration()?;
+        let new_config = common::update_config_field(config, shift, value);
+        self.write_register(Register::Configuration, new_config)
+    }
+
+    fn read_mask_enable(&self) -> Result<MaskEnableFlags, Error<E>> {
+        // Reading clears the alert flags, so keep them until the caller reads them
         let flags = self.read_register(Register::MaskEnable)?;
-        let flags = MaskEnableFlags::from_bits(flags).unwrap();
+        Ok(self.alerts.record(flags))
+    }
 
-        if preserve {
-            self.write_register(Register::MaskEnable, flags.bits())?;
-        }
-        Ok(flags)
+    fn read_flags(&mut self, preserve: bool) -> Result<MaskEnableFlags, Error<E>> {
+        let flags = self.read_mask_enable()?;
+        let alerts = match preserve {
+            true => self.alerts.peek(),
+            false => self.alerts.take(),
+        };
+        Ok(flags | alerts)
     }
 
-    fn set_flag(&mut self, flag: MaskEnableFlags, enabled: bool) -> Result<(), E> {
-        let flags = self.read_register(Register::MaskEnable)?;
-        let mut new_flags = MaskEnableFlags::from_bits(flags).unwrap();
-        new_flags.set(flag, enabled);
-        self.write_register(Register::MaskEnable, new_flags.bits())
+    fn set_flag(&mut self, flag: MaskEnableFlags, enabled: bool) -> Result<(), Error<E>> {
+        let mut control = self.get_alert_control()?;
+        control.set(flag, enabled);
+        self.set_alert_control(control)
     }
 }
@@ -0,0 +1,42 @@
+use hal::i2c::ErrorKind;
+
+/// Represents an error returned by the INA3221 driver
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error<E> {
+    /// An error occurred on the underlying I2C bus
+    I2c(E),
+    /// The channel index is outside of the range of 0-2, or a slice of channel values has fewer
+    /// than three elements
+    InvalidChannel,
+    /// No shunt resistance has been configured for the channel
+    MissingShuntResistance,
+    /// The limit cannot be represented by the limit register
+    LimitOutOfRange,
+    /// The conversion did not complete within the expected time
+    Timeout,
+    /// No monitoring channels are enabled, so there is nothing to measure
+    NoChannelsEnabled,
+    /// An error occurred while waiting on an alert pin
+    Pin,
+    /// An error occurred while waiting on the delay between conversion polls
+    Delay,
+    /// The device at the address did not identify as a genuine INA3221
+    UnexpectedDevice {
+        /// Manufacturer ID reported by the device
+        manufacturer_id: u16,
+        /// Die ID reported by the device
+        die_id: u16,
+    },
+}
+
+impl<E> hal::i2c::Error for Error<E>
+where
+    E: hal::i2c::Error,
+{
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Error::I2c(error) => error.kind(),
+            _ => ErrorKind::Other,
+        }
+    }
+}
@@ -35,3 +35,31 @@ bitflags! {
         const SUMMATION_CONTROL_3 = 0x4000;
     }
 }
+
+impl MaskEnableFlags {
+    /// Alert flags that are cleared when the Mask/Enable register is read
+    pub(crate) const ALERT_FLAGS: MaskEnableFlags = MaskEnableFlags::from_bits_truncate(
+        MaskEnableFlags::WARNING_ALERT_1.bits()
+            | MaskEnableFlags::WARNING_ALERT_2.bits()
+            | MaskEnableFlags::WARNING_ALERT_3.bits()
+            | MaskEnableFlags::SUMMATION_ALERT.bits()
+            | MaskEnableFlags::CRITICAL_ALERT_1.bits()
+            | MaskEnableFlags::CRITICAL_ALERT_2.bits()
+            | MaskEnableFlags::CRITICAL_ALERT_3.bits(),
+    );
+    /// Status flags reported by the INA3221, as opposed to the latch and summation control flags
+    pub(crate) const STATUS_FLAGS: MaskEnableFlags = MaskEnableFlags::from_bits_truncate(
+        MaskEnableFlags::ALERT_FLAGS.bits()
+            | MaskEnableFlags::POWER_VALID_ALERT.bits()
+            | MaskEnableFlags::TIMING_CONTROL_ALERT.bits()
+            | MaskEnableFlags::CONVERSION_READY.bits(),
+    );
+    /// Latch and summation control flags, which are kept when the Mask/Enable register is read
+    pub(crate) const CONTROL_FLAGS: MaskEnableFlags = MaskEnableFlags::from_bits_truncate(
+        MaskEnableFlags::CRITICAL_ALERT_LATCH.bits()
+            | MaskEnableFlags::WARNING_ALERT_LATCH.bits()
+            | MaskEnableFlags::SUMMATION_CONTROL_1.bits()
+            | MaskEnableFlags::SUMMATION_CONTROL_2.bits()
+            | MaskEnableFlags::SUMMATION_CONTROL_3.bits(),
+    );
+}
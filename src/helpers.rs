@@ -1,17 +1,214 @@
-pub fn convert_to_12bit_signed(value: i32) -> u16 {
-    let value = match value < 0 {
-        true => !value + 1,
-        false => value,
-    };
+// LSB = 40uV for shunt voltages, 8mV (8000uV) for bus voltages
+const SHUNT_VOLTAGE_LSB: i32 = 40;
+const BUS_VOLTAGE_LSB: i32 = 8000;
 
-    (value << 3) as u16
+// Range of a 13-bit two's complement value (sign + 12 bits)
+const MIN_13BIT_SIGNED: i32 = -4096;
+const MAX_13BIT_SIGNED: i32 = 4095;
+
+// Range of a 15-bit two's complement value (sign + 14 bits)
+const MIN_15BIT_SIGNED: i32 = -16384;
+const MAX_15BIT_SIGNED: i32 = 16383;
+
+pub fn convert_to_13bit_signed(value: i32) -> Option<u16> {
+    if !(MIN_13BIT_SIGNED..=MAX_13BIT_SIGNED).contains(&value) {
+        return None;
+    }
+
+    // Two's complement value stored in bits 15-3, bits 2-0 are reserved
+    Some(((value as i16) << 3) as u16)
+}
+
+pub fn convert_from_13bit_signed(value: u16) -> i32 {
+    // Arithmetic shift keeps the sign of the two's complement value
+    ((value as i16) >> 3) as i32
+}
+
+pub fn convert_to_15bit_signed(value: i32) -> Option<u16> {
+    if !(MIN_15BIT_SIGNED..=MAX_15BIT_SIGNED).contains(&value) {
+        return None;
+    }
+
+    // Two's complement value stored in bits 15-1, bit 0 is reserved
+    Some(((value as i16) << 1) as u16)
+}
+
+pub fn convert_from_15bit_signed(value: u16) -> i32 {
+    // Arithmetic shift keeps the sign of the two's complement value
+    ((value as i16) >> 1) as i32
+}
+
+pub fn encode_shunt_voltage(micro_volts: i32) -> Option<u16> {
+    // Truncates towards zero, so limits never exceed the requested voltage
+    convert_to_13bit_signed(micro_volts / SHUNT_VOLTAGE_LSB)
+}
+
+pub fn decode_shunt_voltage(value: u16) -> i32 {
+    convert_from_13bit_signed(value) * SHUNT_VOLTAGE_LSB
+}
+
+pub fn encode_bus_voltage(micro_volts: i32) -> Option<u16> {
+    // Truncates towards zero, so limits never exceed the requested voltage
+    convert_to_13bit_signed(micro_volts / BUS_VOLTAGE_LSB)
+}
+
+pub fn decode_bus_voltage(value: u16) -> i32 {
+    convert_from_13bit_signed(value) * BUS_VOLTAGE_LSB
+}
+
+pub fn encode_shunt_voltage_sum(micro_volts: i32) -> Option<u16> {
+    // Truncates towards zero, so limits never exceed the requested voltage
+    convert_to_15bit_signed(micro_volts / SHUNT_VOLTAGE_LSB)
+}
+
+pub fn decode_shunt_voltage_sum(value: u16) -> i32 {
+    convert_from_15bit_signed(value) * SHUNT_VOLTAGE_LSB
 }
 
-pub fn convert_from_12bit_signed(value: u16) -> i32 {
-    let value = match value & 0x8000 > 0 {
-        true => !value + 1,
-        false => value,
-    };
+pub fn calculate_current(shunt_micro_volts: i32, shunt_milli_ohms: u32) -> i32 {
+    // I = V / R, scaled to micro-amps using 64-bit integers to avoid overflow
+    let micro_amps = shunt_micro_volts as i64 * 1_000 / shunt_milli_ohms as i64;
+    micro_amps.clamp(i32::MIN as i64, i32::MAX as i64) as i32
+}
+
+pub fn calculate_power(micro_amps: i32, micro_volts: i32) -> i64 {
+    // P = I * V, scaled to micro-watts, cannot overflow 64-bit integers
+    micro_amps as i64 * micro_volts as i64 / 1_000_000
+}
+
+pub fn calculate_shunt_voltage(micro_amps: i32, shunt_milli_ohms: u32) -> Option<i32> {
+    // V = I * R, scaled to micro-volts using 64-bit integers to avoid overflow
+    let micro_volts = micro_amps as i64 * shunt_milli_ohms as i64 / 1_000;
+    i32::try_from(micro_volts).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_13bit_signed_round_trips_every_value() {
+        for value in MIN_13BIT_SIGNED..=MAX_13BIT_SIGNED {
+            let encoded = convert_to_13bit_signed(value).unwrap();
+            assert_eq!(encoded & 0x0007, 0, "reserved bits set for {}", value);
+            assert_eq!(convert_from_13bit_signed(encoded), value);
+        }
+    }
+
+    #[test]
+    fn convert_13bit_signed_decodes_every_register_value() {
+        for raw in 0..=u16::MAX {
+            let value = convert_from_13bit_signed(raw);
+            assert!((MIN_13BIT_SIGNED..=MAX_13BIT_SIGNED).contains(&value));
+            assert_eq!(convert_to_13bit_signed(value), Some(raw & 0xFFF8));
+        }
+    }
+
+    #[test]
+    fn convert_13bit_signed_rejects_out_of_range() {
+        assert_eq!(convert_to_13bit_signed(MAX_13BIT_SIGNED + 1), None);
+        assert_eq!(convert_to_13bit_signed(MIN_13BIT_SIGNED - 1), None);
+        assert_eq!(convert_to_13bit_signed(i32::MAX), None);
+        assert_eq!(convert_to_13bit_signed(i32::MIN), None);
+    }
+
+    #[test]
+    fn convert_15bit_signed_round_trips_every_value() {
+        for value in MIN_15BIT_SIGNED..=MAX_15BIT_SIGNED {
+            let encoded = convert_to_15bit_signed(value).unwrap();
+            assert_eq!(encoded & 0x0001, 0, "reserved bit set for {}", value);
+            assert_eq!(convert_from_15bit_signed(encoded), value);
+        }
+    }
+
+    #[test]
+    fn convert_15bit_signed_decodes_every_register_value() {
+        for raw in 0..=u16::MAX {
+            let value = convert_from_15bit_signed(raw);
+            assert!((MIN_15BIT_SIGNED..=MAX_15BIT_SIGNED).contains(&value));
+            assert_eq!(convert_to_15bit_signed(value), Some(raw & 0xFFFE));
+        }
+    }
+
+    #[test]
+    fn convert_15bit_signed_rejects_out_of_range() {
+        assert_eq!(convert_to_15bit_signed(MAX_15BIT_SIGNED + 1), None);
+        assert_eq!(convert_to_15bit_signed(MIN_15BIT_SIGNED - 1), None);
+    }
+
+    #[test]
+    fn shunt_voltage_matches_datasheet() {
+        // Full-scale is +163.8mV and -163.84mV
+        assert_eq!(decode_shunt_voltage(0x7FF8), 163_800);
+        assert_eq!(decode_shunt_voltage(0x8000), -163_840);
+        assert_eq!(decode_shunt_voltage(0x0008), 40);
+        assert_eq!(decode_shunt_voltage(0xFFF8), -40);
+        assert_eq!(decode_shunt_voltage(0x0000), 0);
+
+        assert_eq!(encode_shunt_voltage(163_800), Some(0x7FF8));
+        assert_eq!(encode_shunt_voltage(-163_840), Some(0x8000));
+        assert_eq!(encode_shunt_voltage(-40), Some(0xFFF8));
+        assert_eq!(encode_shunt_voltage(163_840), None);
+        assert_eq!(encode_shunt_voltage(-163_880), None);
+    }
+
+    #[test]
+    fn shunt_voltage_preserves_sign() {
+        for raw in (0..=u16::MAX).step_by(8) {
+            let micro_volts = decode_shunt_voltage(raw);
+            assert_eq!(micro_volts < 0, raw & 0x8000 > 0);
+            assert_eq!(encode_shunt_voltage(micro_volts), Some(raw));
+        }
+    }
+
+    #[test]
+    fn shunt_voltage_truncates_towards_zero() {
+        assert_eq!(encode_shunt_voltage(79), Some(0x0008));
+        assert_eq!(encode_shunt_voltage(-79), Some(0xFFF8));
+        assert_eq!(encode_shunt_voltage(39), Some(0x0000));
+        assert_eq!(encode_shunt_voltage(-39), Some(0x0000));
+    }
+
+    #[test]
+    fn bus_voltage_matches_datasheet() {
+        // Power valid defaults are 10V (upper) and 9V (lower)
+        assert_eq!(decode_bus_voltage(0x2710), 10_000_000);
+        assert_eq!(decode_bus_voltage(0x2328), 9_000_000);
+        assert_eq!(decode_bus_voltage(0x7FF8), 32_760_000);
+
+        assert_eq!(encode_bus_voltage(10_000_000), Some(0x2710));
+        assert_eq!(encode_bus_voltage(9_000_000), Some(0x2328));
+        assert_eq!(encode_bus_voltage(32_760_000), Some(0x7FF8));
+        assert_eq!(encode_bus_voltage(32_768_000), None);
+    }
+
+    #[test]
+    fn bus_voltage_round_trips_every_register_value() {
+        for raw in (0..=u16::MAX).step_by(8) {
+            assert_eq!(encode_bus_voltage(decode_bus_voltage(raw)), Some(raw));
+        }
+    }
+
+    #[test]
+    fn shunt_voltage_sum_matches_datasheet() {
+        // Full-scale is +655.32mV and -655.36mV, default limit is 0x7FFE
+        assert_eq!(decode_shunt_voltage_sum(0x7FFE), 655_320);
+        assert_eq!(decode_shunt_voltage_sum(0x8000), -655_360);
+        assert_eq!(decode_shunt_voltage_sum(0x0002), 40);
+        assert_eq!(decode_shunt_voltage_sum(0xFFFE), -40);
+
+        assert_eq!(encode_shunt_voltage_sum(655_320), Some(0x7FFE));
+        assert_eq!(encode_shunt_voltage_sum(-655_360), Some(0x8000));
+        assert_eq!(encode_shunt_voltage_sum(655_360), None);
+    }
 
-    (value >> 3) as i32
+    #[test]
+    fn shunt_voltage_sum_round_trips_every_register_value() {
+        for raw in (0..=u16::MAX).step_by(2) {
+            assert_eq!(
+                encode_shunt_voltage_sum(decode_shunt_voltage_sum(raw)),
+                Some(raw)
+            );
+        }
+    }
 }
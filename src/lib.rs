@@ -3,19 +3,54 @@
 //! Provides a platform agnostic driver for the [INA3221] triple-channel current and power monitor
 //! that can be used with any [embedded-hal] v1.0 blocking I2C implementation.
 //!
+//! An async driver built on [embedded-hal-async] is available in the [`asynch`] module
+//! when the `async` feature is enabled.
+//!
+//! I2C traffic can be captured and replayed with the [`record`] module.
+//!
+//! A simulated INA3221 for host-side testing is available in the [`sim`] module
+//! when the `sim` feature is enabled, which requires `std`.
+//!
 //! [INA3221]: https://www.ti.com/lit/ds/symlink/ina3221.pdf
 //! [embedded-hal]: https://docs.rs/embedded-hal/1.0.0/embedded_hal/
+//! [embedded-hal-async]: https://docs.rs/embedded-hal-async/
 #![no_std]
 extern crate embedded_hal as hal;
+#[cfg(feature = "sim")]
+extern crate std;
 
+mod address;
+mod alert;
+#[cfg(feature = "async")]
+pub mod asynch;
+mod channel;
+mod common;
+mod config;
 mod driver;
+mod error;
 mod flags;
 mod helpers;
 mod mode;
+mod monitor;
+mod power;
 pub mod prelude;
+pub mod record;
 mod registers;
+mod scan;
+#[cfg(feature = "sim")]
+pub mod sim;
+mod snapshot;
 
+pub use address::Address;
+pub use alert::{AlertEvent, AlertKind, AlertLine, AlertStatus};
+pub use channel::{Channel, InvalidChannel};
+pub use config::{Configuration, ConfigurationBuilder};
 pub use driver::INA3221;
+pub use error::Error;
 pub use flags::MaskEnableFlags;
-pub use mode::OperatingMode;
+pub use mode::{Averaging, ConversionTime, Measurement, OperatingMode};
+pub use monitor::PowerMonitor;
 pub use ohms::*;
+pub use power::Power;
+pub use scan::{scan, ScanResult};
+pub use snapshot::{ChannelReading, Snapshot};
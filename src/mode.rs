@@ -1,10 +1,191 @@
+/// Represents which measurements are performed by the INA3221
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Measurement {
+    /// Only shunt voltage measurements are performed
+    Shunt = 0x01,
+    /// Only bus voltage measurements are performed
+    Bus = 0x02,
+    /// Both shunt and bus voltage measurements are performed
+    ShuntAndBus = 0x03,
+}
+
+impl Measurement {
+    pub(crate) fn from_bits(bits: u16) -> Option<Measurement> {
+        match bits & 0x3 {
+            0x01 => Some(Measurement::Shunt),
+            0x02 => Some(Measurement::Bus),
+            0x03 => Some(Measurement::ShuntAndBus),
+            _ => None,
+        }
+    }
+}
+
 /// Represents the operating mode of the INA3221
-#[derive(Debug)]
+///
+/// The mode describes *when* measurements are performed (once, continuously, or not at all),
+/// and *what* is measured by each cycle as a [`Measurement`].
+///
+/// Each of the eight mode encodings of the Configuration register has a distinct value,
+/// so reading and writing the mode is lossless.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OperatingMode {
-    /// Power save mode, no measurements are performed
-    PowerDown = 0x00,
-    /// Shunt and bus voltage measurements are performed once
-    Triggered = 0x03,
-    /// Shunt and bus voltage measurements are performed continuously
-    Continuous = 0x07,
+    /// Power save mode, no measurements are performed (mode bits `000`)
+    PowerDown,
+    /// Measurements are performed once, then the device powers down (mode bits `001`-`011`)
+    Triggered(Measurement),
+    /// Measurements are performed continuously (mode bits `101`-`111`)
+    Continuous(Measurement),
+    /// Power save mode with the continuous bit set (mode bits `100`)
+    ///
+    /// The INA3221 behaves exactly the same as `PowerDown`, this is kept distinct so that
+    /// the mode bits are preserved when read back
+    ContinuousPowerDown,
+}
+
+impl OperatingMode {
+    /// Gets the measurements performed in this mode, if any
+    pub fn measurement(&self) -> Option<Measurement> {
+        match self {
+            OperatingMode::Triggered(measurement) | OperatingMode::Continuous(measurement) => {
+                Some(*measurement)
+            }
+            _ => None,
+        }
+    }
+
+    /// Checks if this mode powers down the device, no measurements are performed
+    pub fn is_power_down(&self) -> bool {
+        self.measurement().is_none()
+    }
+
+    /// Checks if the continuous bit is set in this mode
+    pub fn is_continuous(&self) -> bool {
+        matches!(
+            self,
+            OperatingMode::Continuous(_) | OperatingMode::ContinuousPowerDown
+        )
+    }
+
+    pub(crate) fn bits(&self) -> u16 {
+        match self {
+            OperatingMode::PowerDown => 0x00,
+            OperatingMode::Triggered(measurement) => *measurement as u16,
+            OperatingMode::ContinuousPowerDown => 0x04,
+            OperatingMode::Continuous(measurement) => 0x04 | *measurement as u16,
+        }
+    }
+
+    pub(crate) fn from_bits(bits: u16) -> OperatingMode {
+        let continuous = bits & 0x04 > 0;
+        match (continuous, Measurement::from_bits(bits)) {
+            (false, None) => OperatingMode::PowerDown,
+            (false, Some(measurement)) => OperatingMode::Triggered(measurement),
+            (true, None) => OperatingMode::ContinuousPowerDown,
+            (true, Some(measurement)) => OperatingMode::Continuous(measurement),
+        }
+    }
+}
+
+/// Represents the number of samples that are collected and averaged together
+///
+/// Averaging applies to both the shunt and bus voltage measurements of all channels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Averaging {
+    /// 1 sample (no averaging)
+    Samples1 = 0x00,
+    /// 4 samples
+    Samples4 = 0x01,
+    /// 16 samples
+    Samples16 = 0x02,
+    /// 64 samples
+    Samples64 = 0x03,
+    /// 128 samples
+    Samples128 = 0x04,
+    /// 256 samples
+    Samples256 = 0x05,
+    /// 512 samples
+    Samples512 = 0x06,
+    /// 1024 samples
+    Samples1024 = 0x07,
+}
+
+impl Averaging {
+    /// Gets the number of samples that are averaged together
+    pub fn samples(&self) -> u16 {
+        match self {
+            Averaging::Samples1 => 1,
+            Averaging::Samples4 => 4,
+            Averaging::Samples16 => 16,
+            Averaging::Samples64 => 64,
+            Averaging::Samples128 => 128,
+            Averaging::Samples256 => 256,
+            Averaging::Samples512 => 512,
+            Averaging::Samples1024 => 1024,
+        }
+    }
+
+    pub(crate) fn from_bits(bits: u16) -> Averaging {
+        match bits & 0x7 {
+            0x00 => Averaging::Samples1,
+            0x01 => Averaging::Samples4,
+            0x02 => Averaging::Samples16,
+            0x03 => Averaging::Samples64,
+            0x04 => Averaging::Samples128,
+            0x05 => Averaging::Samples256,
+            0x06 => Averaging::Samples512,
+            _ => Averaging::Samples1024,
+        }
+    }
+}
+
+/// Represents the conversion time of a single shunt or bus voltage measurement
+///
+/// Longer conversion times reduce noise at the cost of a slower measurement cycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConversionTime {
+    /// 140 µs
+    Micros140 = 0x00,
+    /// 204 µs
+    Micros204 = 0x01,
+    /// 332 µs
+    Micros332 = 0x02,
+    /// 588 µs
+    Micros588 = 0x03,
+    /// 1.1 ms
+    Micros1100 = 0x04,
+    /// 2.116 ms
+    Micros2116 = 0x05,
+    /// 4.156 ms
+    Micros4156 = 0x06,
+    /// 8.244 ms
+    Micros8244 = 0x07,
+}
+
+impl ConversionTime {
+    /// Gets the conversion time in microseconds
+    pub fn micros(&self) -> u32 {
+        match self {
+            ConversionTime::Micros140 => 140,
+            ConversionTime::Micros204 => 204,
+            ConversionTime::Micros332 => 332,
+            ConversionTime::Micros588 => 588,
+            ConversionTime::Micros1100 => 1100,
+            ConversionTime::Micros2116 => 2116,
+            ConversionTime::Micros4156 => 4156,
+            ConversionTime::Micros8244 => 8244,
+        }
+    }
+
+    pub(crate) fn from_bits(bits: u16) -> ConversionTime {
+        match bits & 0x7 {
+            0x00 => ConversionTime::Micros140,
+            0x01 => ConversionTime::Micros204,
+            0x02 => ConversionTime::Micros332,
+            0x03 => ConversionTime::Micros588,
+            0x04 => ConversionTime::Micros1100,
+            0x05 => ConversionTime::Micros2116,
+            0x06 => ConversionTime::Micros4156,
+            _ => ConversionTime::Micros8244,
+        }
+    }
 }
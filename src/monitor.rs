@@ -0,0 +1,105 @@
+use crate::{Channel, Error, Power, INA3221};
+use core::convert::TryFrom;
+use hal::i2c::I2c;
+use ohms::{Current, Voltage};
+
+/// Common interface of current and power monitors with one or more monitoring channels
+///
+/// Allows logging and protection code to be written once for any monitor, such as the INA3221,
+/// INA219, or INA226. Channels are identified by a zero-based index below `channel_count()`.
+///
+/// The methods are named differently from the inherent getters of [`INA3221`], so both can be
+/// used on the same driver without ambiguity:
+///
+/// ```rust
+/// # use ina3221::PowerMonitor;
+/// fn log_power<M: PowerMonitor>(monitor: &mut M) -> Result<(), M::Error> {
+///     for channel in 0..monitor.channel_count() {
+///         let power = monitor.power(channel)?;
+///         // ...
+///     }
+///     Ok(())
+/// }
+/// ```
+pub trait PowerMonitor {
+    /// Error returned by the monitor, including for channel indices out of range
+    type Error;
+
+    /// Gets the number of monitoring channels
+    fn channel_count(&self) -> usize;
+
+    /// Gets the manufacturer ID reported by the monitor
+    fn manufacturer_id(&mut self) -> Result<u16, Self::Error>;
+
+    /// Gets the die ID reported by the monitor
+    fn die_id(&mut self) -> Result<u16, Self::Error>;
+
+    /// Gets the shunt voltage of a specific monitoring channel
+    fn shunt_voltage(&mut self, channel: usize) -> Result<Voltage, Self::Error>;
+
+    /// Gets the bus voltage of a specific monitoring channel
+    fn bus_voltage(&mut self, channel: usize) -> Result<Voltage, Self::Error>;
+
+    /// Gets the current draw of a specific monitoring channel
+    fn current(&mut self, channel: usize) -> Result<Current, Self::Error>;
+
+    /// Gets the power draw of a specific monitoring channel
+    fn power(&mut self, channel: usize) -> Result<Power, Self::Error>;
+
+    /// Gets the current that triggers an alert on a specific monitoring channel
+    fn current_limit(&mut self, channel: usize) -> Result<Current, Self::Error>;
+
+    /// Sets the current that triggers an alert on a specific monitoring channel
+    fn set_current_limit(&mut self, channel: usize, limit: Current) -> Result<(), Self::Error>;
+}
+
+/// Uses the critical alert limits, which are compared against every conversion
+///
+/// Channel indices outside of the range of 0-2 return `Error::InvalidChannel`
+impl<I2C, E> PowerMonitor for INA3221<I2C>
+where
+    I2C: I2c<Error = E>,
+{
+    type Error = Error<E>;
+
+    fn channel_count(&self) -> usize {
+        Channel::ALL.len()
+    }
+
+    fn manufacturer_id(&mut self) -> Result<u16, Self::Error> {
+        self.get_manufacturer_id()
+    }
+
+    fn die_id(&mut self) -> Result<u16, Self::Error> {
+        self.get_die_id()
+    }
+
+    fn shunt_voltage(&mut self, channel: usize) -> Result<Voltage, Self::Error> {
+        self.get_shunt_voltage(to_channel(channel)?)
+    }
+
+    fn bus_voltage(&mut self, channel: usize) -> Result<Voltage, Self::Error> {
+        self.get_bus_voltage(to_channel(channel)?)
+    }
+
+    fn current(&mut self, channel: usize) -> Result<Current, Self::Error> {
+        self.get_current(to_channel(channel)?)
+    }
+
+    fn power(&mut self, channel: usize) -> Result<Power, Self::Error> {
+        self.get_power(to_channel(channel)?)
+    }
+
+    fn current_limit(&mut self, channel: usize) -> Result<Current, Self::Error> {
+        self.get_critical_current_limit(to_channel(channel)?)
+    }
+
+    fn set_current_limit(&mut self, channel: usize, limit: Current) -> Result<(), Self::Error> {
+        self.set_critical_current_limit(to_channel(channel)?, limit)
+    }
+}
+
+fn to_channel<E>(index: usize) -> Result<Channel, Error<E>> {
+    let index = u8::try_from(index).map_err(|_| Error::InvalidChannel)?;
+    Ok(Channel::try_from(index)?)
+}
@@ -0,0 +1,57 @@
+use core::ops::{Add, Sub};
+
+/// Represents an amount of electrical power
+///
+/// Power is stored as signed micro-watts, so a reverse current results in a negative power
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct Power {
+    micro_watts: i64,
+}
+
+impl Power {
+    /// Creates a new power value from micro-watts
+    pub const fn from_micro_watts(micro_watts: i64) -> Power {
+        Power { micro_watts }
+    }
+
+    /// Creates a new power value from milli-watts
+    pub const fn from_milli_watts(milli_watts: i64) -> Power {
+        Power::from_micro_watts(milli_watts * 1_000)
+    }
+
+    /// Gets the power in micro-watts
+    pub fn micro_watts(&self) -> i64 {
+        self.micro_watts
+    }
+
+    /// Gets the power in milli-watts
+    pub fn milli_watts(&self) -> f32 {
+        self.micro_watts as f32 / 1_000.0
+    }
+
+    /// Gets the power in watts
+    pub fn watts(&self) -> f32 {
+        self.micro_watts as f32 / 1_000_000.0
+    }
+
+    /// Checks if the power is zero
+    pub fn is_zero(&self) -> bool {
+        self.micro_watts == 0
+    }
+}
+
+impl Add for Power {
+    type Output = Power;
+
+    fn add(self, other: Power) -> Power {
+        Power::from_micro_watts(self.micro_watts + other.micro_watts)
+    }
+}
+
+impl Sub for Power {
+    type Output = Power;
+
+    fn sub(self, other: Power) -> Power {
+        Power::from_micro_watts(self.micro_watts - other.micro_watts)
+    }
+}
@@ -1,5 +1,14 @@
+pub use crate::address::Address;
+pub use crate::alert::{AlertEvent, AlertKind, AlertLine, AlertStatus};
+pub use crate::channel::{Channel, InvalidChannel};
+pub use crate::config::{Configuration, ConfigurationBuilder};
 pub use crate::driver::INA3221;
+pub use crate::error::Error;
 pub use crate::flags::MaskEnableFlags;
-pub use crate::mode::OperatingMode;
+pub use crate::mode::{Averaging, ConversionTime, Measurement, OperatingMode};
+pub use crate::monitor::PowerMonitor;
+pub use crate::power::Power;
+pub use crate::scan::ScanResult;
+pub use crate::snapshot::{ChannelReading, Snapshot};
 
 pub use ohms::prelude::*;
@@ -0,0 +1,634 @@
+//! Recording and replaying of I2C traffic
+//!
+//! [`Recorder`] wraps any blocking [`I2c`] implementation and logs every transaction performed
+//! through it, so the exact traffic between the driver and a misbehaving device can be captured.
+//! [`Replay`] implements [`I2c`] from a captured log and feeds it back into the driver, so a bug
+//! report can be turned into a regression test that runs without hardware.
+//!
+//! The log is written to any [`core::fmt::Write`] sink, so no allocator is required.
+//!
+//! # Log Format
+//!
+//! Every transaction is written as one line of whitespace separated fields:
+//!
+//! ```text
+//! 1200 40 WFE RFE=5449
+//! 1350 40 W00=7127
+//! 1500 41 WFE RFE=0000 !nack-address
+//! ```
+//!
+//! 1. The timestamp of the start of the transaction in microseconds, as reported by the [`Clock`]
+//! 2. The 7-bit address of the device in hex
+//! 3. One field per operation, in order:
+//!    - `W` followed by the register pointer and `=` with the data written, if any
+//!    - `R` followed by the register pointer the data was read from and `=` with the data read
+//! 4. `!` followed by the error, if the transaction failed
+//!
+//! The register of a read is the pointer set by the last write to the same device,
+//! or `??` if it is not known. All bytes are written in hex, in the order they were transferred.
+//!
+//! Blank lines and lines starting with `#` are ignored when replaying, so logs can be annotated.
+//!
+//! # Example
+//!
+//! Record the traffic of a field unit into any sink, such as a fixed-size string buffer:
+//!
+//! ```rust,ignore
+//! use ina3221::record::Recorder;
+//! use ina3221::{Address, INA3221};
+//!
+//! let recorder = Recorder::new(i2c, log_buffer, || timer.micros());
+//! let ina = INA3221::new(recorder, Address::Gnd);
+//! let power = ina.get_power(Channel::Ch1)?;
+//!
+//! let (i2c, log_buffer) = ina.destroy().destroy();
+//! ```
+//!
+//! Then replay the captured log on the host:
+//!
+//! ```rust
+//! use ina3221::record::Replay;
+//! use ina3221::{Address, INA3221};
+//!
+//! let log = "\
+//! ## Configuration register read from a field unit
+//! 1200 40 W00 R00=7127
+//! ";
+//!
+//! let mut replay = Replay::new(log);
+//! let ina = INA3221::new(&mut replay, Address::Gnd);
+//! assert_eq!(ina.get_configuration().unwrap(), 0x7127);
+//! assert!(replay.is_finished());
+//! ```
+
+use core::fmt::{self, Write};
+use core::str::SplitWhitespace;
+use hal::i2c::{Error, ErrorKind, ErrorType, I2c, NoAcknowledgeSource, Operation};
+
+/// Source of the timestamps written to the log by a [`Recorder`]
+///
+/// Implemented for closures returning the current time in microseconds,
+/// so `|| 0` can be used when timestamps are not needed
+pub trait Clock {
+    /// Gets the current time in microseconds
+    fn now_micros(&mut self) -> u64;
+}
+
+impl<F> Clock for F
+where
+    F: FnMut() -> u64,
+{
+    fn now_micros(&mut self) -> u64 {
+        self()
+    }
+}
+
+/// I2C wrapper that logs every transaction to a text sink
+///
+/// See the [module documentation](self) for the log format.
+#[derive(Debug)]
+pub struct Recorder<I2C, W, C> {
+    i2c: I2C,
+    log: Log<W>,
+    clock: C,
+}
+
+#[derive(Debug)]
+struct Log<W> {
+    sink: W,
+    pointer: Option<(u8, u8)>,
+    truncated: bool,
+}
+
+impl<I2C, W, C> Recorder<I2C, W, C>
+where
+    I2C: I2c,
+    W: Write,
+    C: Clock,
+{
+    /// Creates a recorder that logs the transactions of an I2C peripheral to a sink
+    pub fn new(i2c: I2C, sink: W, clock: C) -> Recorder<I2C, W, C> {
+        Recorder {
+            i2c,
+            log: Log {
+                sink,
+                pointer: None,
+                truncated: false,
+            },
+            clock,
+        }
+    }
+
+    /// Gets the sink the log is written to
+    pub fn log(&self) -> &W {
+        &self.log.sink
+    }
+
+    /// Checks if the sink failed to accept part of the log, such as a full buffer
+    ///
+    /// Nothing is logged after the first failure, but the transactions themselves are not affected
+    pub fn is_truncated(&self) -> bool {
+        self.log.truncated
+    }
+
+    /// Destroys the recorder, returning the I2C peripheral and the sink
+    pub fn destroy(self) -> (I2C, W) {
+        (self.i2c, self.log.sink)
+    }
+
+    fn begin(&mut self, address: u8) {
+        let timestamp = self.clock.now_micros();
+        self.log
+            .write(format_args!("{} {:02X}", timestamp, address));
+    }
+}
+
+impl<W: Write> Log<W> {
+    fn write(&mut self, args: fmt::Arguments) {
+        // Stop at the first failure, so the log is not corrupted by a partially written field
+        if !self.truncated && self.sink.write_fmt(args).is_err() {
+            self.truncated = true;
+        }
+    }
+
+    fn write_op<B>(&mut self, address: u8, bytes: B)
+    where
+        B: IntoIterator<Item = u8>,
+    {
+        self.write(format_args!(" W"));
+        for (index, byte) in bytes.into_iter().enumerate() {
+            self.write_byte(address, index, byte);
+        }
+    }
+
+    fn write_byte(&mut self, address: u8, index: usize, byte: u8) {
+        // The first byte written sets the register pointer
+        if index == 0 {
+            self.pointer = Some((address, byte));
+        } else if index == 1 {
+            self.write(format_args!("="));
+        }
+        self.write(format_args!("{:02X}", byte));
+    }
+
+    fn read_op(&mut self, address: u8, buffer: Option<&[u8]>, len: usize) {
+        match self.pointer {
+            Some((pointer_address, register)) if pointer_address == address => {
+                self.write(format_args!(" R{:02X}=", register))
+            }
+            _ => self.write(format_args!(" R??=")),
+        }
+
+        match buffer {
+            Some(buffer) => buffer
+                .iter()
+                .for_each(|byte| self.write(format_args!("{:02X}", byte))),
+            None => (0..len).for_each(|_| self.write(format_args!("??"))),
+        }
+    }
+
+    fn end<E: Error>(&mut self, result: &Result<(), E>) {
+        if let Err(error) = result {
+            self.write(format_args!(" !{}", ErrorToken(error.kind())));
+        }
+        self.write(format_args!("\n"));
+    }
+}
+
+/// Iterator that logs the bytes written as they are consumed by the I2C peripheral
+struct WriteTap<'l, W, B> {
+    log: &'l mut Log<W>,
+    address: u8,
+    bytes: B,
+    index: usize,
+}
+
+impl<'l, W, B> Iterator for WriteTap<'l, W, B>
+where
+    W: Write,
+    B: Iterator<Item = u8>,
+{
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        let byte = self.bytes.next()?;
+        self.log.write_byte(self.address, self.index, byte);
+        self.index += 1;
+        Some(byte)
+    }
+}
+
+impl<I2C, W, C> ErrorType for Recorder<I2C, W, C>
+where
+    I2C: ErrorType,
+{
+    type Error = I2C::Error;
+}
+
+impl<I2C, W, C> I2c for Recorder<I2C, W, C>
+where
+    I2C: I2c,
+    W: Write,
+    C: Clock,
+{
+    fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.begin(address);
+        let result = self.i2c.read(address, buffer);
+        self.log.read_op(address, Some(buffer), buffer.len());
+        self.log.end(&result);
+        result
+    }
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.begin(address);
+        let result = self.i2c.write(address, bytes);
+        self.log.write_op(address, bytes.iter().copied());
+        self.log.end(&result);
+        result
+    }
+
+    fn write_iter<B>(&mut self, address: u8, bytes: B) -> Result<(), Self::Error>
+    where
+        B: IntoIterator<Item = u8>,
+    {
+        self.begin(address);
+        self.log.write(format_args!(" W"));
+        let tap = WriteTap {
+            log: &mut self.log,
+            address,
+            bytes: bytes.into_iter(),
+            index: 0,
+        };
+        let result = self.i2c.write_iter(address, tap);
+        self.log.end(&result);
+        result
+    }
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.begin(address);
+        let result = self.i2c.write_read(address, bytes, buffer);
+        self.log.write_op(address, bytes.iter().copied());
+        self.log.read_op(address, Some(buffer), buffer.len());
+        self.log.end(&result);
+        result
+    }
+
+    fn write_iter_read<B>(
+        &mut self,
+        address: u8,
+        bytes: B,
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error>
+    where
+        B: IntoIterator<Item = u8>,
+    {
+        self.begin(address);
+        self.log.write(format_args!(" W"));
+        let tap = WriteTap {
+            log: &mut self.log,
+            address,
+            bytes: bytes.into_iter(),
+            index: 0,
+        };
+        let result = self.i2c.write_iter_read(address, tap, buffer);
+        self.log.read_op(address, Some(buffer), buffer.len());
+        self.log.end(&result);
+        result
+    }
+
+    fn transaction<'a>(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'a>],
+    ) -> Result<(), Self::Error> {
+        self.begin(address);
+        let result = self.i2c.transaction(address, operations);
+        for operation in operations.iter() {
+            match operation {
+                Operation::Write(bytes) => self.log.write_op(address, bytes.iter().copied()),
+                Operation::Read(buffer) => self.log.read_op(address, Some(buffer), buffer.len()),
+            }
+        }
+        self.log.end(&result);
+        result
+    }
+
+    /// Performs a transaction from an iterator of operations
+    ///
+    /// The read buffers are handed over to the I2C peripheral, so the data read cannot be
+    /// logged and is written as `??` instead. Such transactions cannot be replayed.
+    fn transaction_iter<'a, O>(&mut self, address: u8, operations: O) -> Result<(), Self::Error>
+    where
+        O: IntoIterator<Item = Operation<'a>>,
+    {
+        self.begin(address);
+        let log = &mut self.log;
+        let operations = operations.into_iter().inspect(|operation| match operation {
+            Operation::Write(bytes) => log.write_op(address, bytes.iter().copied()),
+            Operation::Read(buffer) => log.read_op(address, None, buffer.len()),
+        });
+        let result = self.i2c.transaction_iter(address, operations);
+        self.log.end(&result);
+        result
+    }
+}
+
+/// Represents an error returned by a [`Replay`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayError {
+    /// The recorded transaction failed with an error on the bus
+    Bus(ErrorKind),
+    /// The transaction does not match the transaction recorded on the line of the log
+    Mismatch {
+        /// Line number of the recorded transaction, starting at 1
+        line: usize,
+    },
+    /// The line of the log could not be parsed
+    Malformed {
+        /// Line number of the malformed transaction, starting at 1
+        line: usize,
+    },
+    /// Every transaction in the log has already been replayed
+    Finished,
+}
+
+impl Error for ReplayError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            ReplayError::Bus(kind) => *kind,
+            _ => ErrorKind::Other,
+        }
+    }
+}
+
+/// I2C implementation that replays the transactions of a captured log
+///
+/// Every transaction must match the next transaction in the log: the same address,
+/// the same operations in the same order, and the same bytes written.
+/// The recorded bytes are returned for reads, and the recorded error for failed transactions.
+/// Timestamps are ignored, so replays are deterministic.
+///
+/// See the [module documentation](self) for the log format.
+#[derive(Debug, Clone)]
+pub struct Replay<'a> {
+    lines: core::iter::Enumerate<core::str::Lines<'a>>,
+}
+
+impl<'a> Replay<'a> {
+    /// Creates a replay of the transactions in a captured log
+    pub fn new(log: &'a str) -> Replay<'a> {
+        Replay {
+            lines: log.lines().enumerate(),
+        }
+    }
+
+    /// Checks if every transaction in the log has been replayed
+    pub fn is_finished(&self) -> bool {
+        self.lines.clone().all(|(_, line)| is_ignored(line))
+    }
+
+    fn replay<F>(&mut self, address: u8, operations: F) -> Result<(), ReplayError>
+    where
+        F: FnOnce(&mut Entry<'a>) -> Result<(), ReplayError>,
+    {
+        let mut entry = self.next_entry(address)?;
+        operations(&mut entry)?;
+        entry.finish()
+    }
+
+    fn next_entry(&mut self, address: u8) -> Result<Entry<'a>, ReplayError> {
+        let (index, text) = self
+            .lines
+            .find(|(_, text)| !is_ignored(text))
+            .ok_or(ReplayError::Finished)?;
+        let line = index + 1;
+        let malformed = ReplayError::Malformed { line };
+
+        let (text, error) = match text.split_once('!') {
+            Some((text, error)) => (text, Some(parse_error(error.trim()).ok_or(malformed)?)),
+            None => (text, None),
+        };
+
+        let mut fields = text.split_whitespace();
+        fields
+            .next()
+            .and_then(|timestamp| timestamp.parse::<u64>().ok())
+            .ok_or(malformed)?;
+        let recorded = fields
+            .next()
+            .and_then(|address| u8::from_str_radix(address, 16).ok())
+            .ok_or(malformed)?;
+
+        if recorded != address {
+            return Err(ReplayError::Mismatch { line });
+        }
+
+        Ok(Entry {
+            line,
+            operations: fields,
+            error,
+        })
+    }
+}
+
+/// Recorded transaction that is being replayed
+struct Entry<'a> {
+    line: usize,
+    operations: SplitWhitespace<'a>,
+    error: Option<ErrorKind>,
+}
+
+impl<'a> Entry<'a> {
+    fn write<B>(&mut self, bytes: B) -> Result<(), ReplayError>
+    where
+        B: IntoIterator<Item = u8>,
+    {
+        let field = self.next_operation('W')?;
+        let (register, data) = field.split_once('=').unwrap_or((field, ""));
+
+        let mut recorded = hex_bytes(register).chain(hex_bytes(data));
+        let mut bytes = bytes.into_iter();
+        loop {
+            match (recorded.next(), bytes.next()) {
+                (None, None) => return Ok(()),
+                (Some(None), _) => return Err(self.malformed()),
+                (Some(Some(expected)), Some(byte)) if expected == byte => {}
+                _ => return Err(self.mismatch()),
+            }
+        }
+    }
+
+    fn read(&mut self, buffer: &mut [u8]) -> Result<(), ReplayError> {
+        let field = self.next_operation('R')?;
+        let (_, data) = field.split_once('=').ok_or(self.malformed())?;
+
+        let mut recorded = hex_bytes(data);
+        for byte in buffer.iter_mut() {
+            match recorded.next() {
+                Some(Some(value)) => *byte = value,
+                Some(None) => return Err(self.malformed()),
+                None => return Err(self.mismatch()),
+            }
+        }
+
+        match recorded.next() {
+            Some(_) => Err(self.mismatch()),
+            None => Ok(()),
+        }
+    }
+
+    fn operation(&mut self, operation: &mut Operation<'_>) -> Result<(), ReplayError> {
+        match operation {
+            Operation::Write(bytes) => self.write(bytes.iter().copied()),
+            Operation::Read(buffer) => self.read(buffer),
+        }
+    }
+
+    fn finish(mut self) -> Result<(), ReplayError> {
+        if self.operations.next().is_some() {
+            return Err(self.mismatch());
+        }
+
+        match self.error {
+            Some(kind) => Err(ReplayError::Bus(kind)),
+            None => Ok(()),
+        }
+    }
+
+    fn next_operation(&mut self, prefix: char) -> Result<&'a str, ReplayError> {
+        self.operations
+            .next()
+            .and_then(|field| field.strip_prefix(prefix))
+            .ok_or(self.mismatch())
+    }
+
+    fn mismatch(&self) -> ReplayError {
+        ReplayError::Mismatch { line: self.line }
+    }
+
+    fn malformed(&self) -> ReplayError {
+        ReplayError::Malformed { line: self.line }
+    }
+}
+
+impl<'a> ErrorType for Replay<'a> {
+    type Error = ReplayError;
+}
+
+impl<'a> I2c for Replay<'a> {
+    fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.replay(address, |entry| entry.read(buffer))
+    }
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.replay(address, |entry| entry.write(bytes.iter().copied()))
+    }
+
+    fn write_iter<B>(&mut self, address: u8, bytes: B) -> Result<(), Self::Error>
+    where
+        B: IntoIterator<Item = u8>,
+    {
+        self.replay(address, |entry| entry.write(bytes))
+    }
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.replay(address, |entry| {
+            entry.write(bytes.iter().copied())?;
+            entry.read(buffer)
+        })
+    }
+
+    fn write_iter_read<B>(
+        &mut self,
+        address: u8,
+        bytes: B,
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error>
+    where
+        B: IntoIterator<Item = u8>,
+    {
+        self.replay(address, |entry| {
+            entry.write(bytes)?;
+            entry.read(buffer)
+        })
+    }
+
+    fn transaction<'o>(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'o>],
+    ) -> Result<(), Self::Error> {
+        self.replay(address, |entry| {
+            operations
+                .iter_mut()
+                .try_for_each(|operation| entry.operation(operation))
+        })
+    }
+
+    fn transaction_iter<'o, O>(&mut self, address: u8, operations: O) -> Result<(), Self::Error>
+    where
+        O: IntoIterator<Item = Operation<'o>>,
+    {
+        self.replay(address, |entry| {
+            operations
+                .into_iter()
+                .try_for_each(|mut operation| entry.operation(&mut operation))
+        })
+    }
+}
+
+/// Formats an error kind as it is written to the log
+struct ErrorToken(ErrorKind);
+
+impl fmt::Display for ErrorToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let token = match self.0 {
+            ErrorKind::Bus => "bus",
+            ErrorKind::ArbitrationLoss => "arbitration-loss",
+            ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address) => "nack-address",
+            ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data) => "nack-data",
+            ErrorKind::NoAcknowledge(_) => "nack",
+            ErrorKind::Overrun => "overrun",
+            _ => "other",
+        };
+        f.write_str(token)
+    }
+}
+
+fn parse_error(token: &str) -> Option<ErrorKind> {
+    let kind = match token {
+        "bus" => ErrorKind::Bus,
+        "arbitration-loss" => ErrorKind::ArbitrationLoss,
+        "nack-address" => ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address),
+        "nack-data" => ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data),
+        "nack" => ErrorKind::NoAcknowledge(NoAcknowledgeSource::Unknown),
+        "overrun" => ErrorKind::Overrun,
+        "other" => ErrorKind::Other,
+        _ => return None,
+    };
+    Some(kind)
+}
+
+/// Decodes pairs of hex digits, yielding `None` for a pair that is not valid hex
+fn hex_bytes(text: &str) -> impl Iterator<Item = Option<u8>> + '_ {
+    (0..text.len().div_ceil(2)).map(move |index| {
+        text.get(index * 2..index * 2 + 2)
+            .filter(|digits| digits.bytes().all(|digit| digit.is_ascii_hexdigit()))
+            .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+    })
+}
+
+fn is_ignored(line: &str) -> bool {
+    let line = line.trim();
+    line.is_empty() || line.starts_with('#')
+}
@@ -1,6 +1,6 @@
 /// Represents a register on the INA3221
 #[allow(dead_code)]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Register {
     Configuration = 0x00,
     ShuntVoltage1 = 0x01,
@@ -0,0 +1,69 @@
+use crate::common;
+use crate::{Address, Error, INA3221};
+use hal::i2c::I2c;
+
+/// Represents the addresses of the genuine INA3221s found on an I2C bus
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ScanResult {
+    found: [bool; 4],
+}
+
+impl ScanResult {
+    pub(crate) fn insert(&mut self, address: Address) {
+        self.found[address.index()] = true;
+    }
+
+    /// Checks if a genuine INA3221 was found at a specific address
+    pub fn contains(&self, address: Address) -> bool {
+        self.found[address.index()]
+    }
+
+    /// Gets the number of INA3221s found
+    pub fn len(&self) -> usize {
+        self.found.iter().filter(|found| **found).count()
+    }
+
+    /// Checks if no INA3221s were found
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterates over the addresses of all INA3221s found
+    pub fn iter(&self) -> impl Iterator<Item = Address> {
+        let found = self.found;
+        Address::ALL
+            .into_iter()
+            .filter(move |address| found[address.index()])
+    }
+}
+
+/// Scans all four INA3221 addresses on an I2C bus for genuine INA3221s
+///
+/// Each address is probed by reading the manufacturer and die IDs, so addresses that do not
+/// acknowledge or belong to other devices (such as an INA219) are skipped.
+/// Any other I2C error is returned as `Error::I2c`.
+///
+/// ```rust,no_run
+/// # use ina3221::INA3221;
+/// # let mut i2c = ina3221::record::Replay::new("");
+/// let devices = ina3221::scan(&mut i2c).unwrap();
+/// for address in devices.iter() {
+///     let ina = INA3221::new(&mut i2c, address);
+/// }
+/// ```
+pub fn scan<I2C, E>(i2c: &mut I2C) -> Result<ScanResult, Error<E>>
+where
+    I2C: I2c<Error = E>,
+    E: hal::i2c::Error,
+{
+    let mut result = ScanResult::default();
+
+    for address in Address::ALL {
+        let probe = INA3221::probe(&mut *i2c, address).map(|_| ());
+        if common::is_device_found(probe)? {
+            result.insert(address);
+        }
+    }
+
+    Ok(result)
+}
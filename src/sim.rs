@@ -0,0 +1,504 @@
+//! Simulated INA3221 for host-side testing
+//!
+//! Requires the `sim` feature to be enabled, which depends on `std`.
+//!
+//! [`SimulatedINA3221`] implements the blocking [`I2c`] trait as a register-accurate INA3221,
+//! so the driver can be exercised without hardware:
+//!
+//! - Writing one byte sets the register pointer, writing three bytes also writes the register
+//! - Reads return the register at the pointer, which does not auto-increment
+//! - Read-only registers and reserved bits ignore writes
+//! - Reading the Mask/Enable register clears the conversion ready and alert flags
+//! - Setting the reset bit restores every register to its power-on value
+//! - Injected shunt and bus voltages are converted into the measurement registers and compared
+//!   against the limit registers to raise the alert flags
+//!
+//! Faults can be injected into individual transactions using [`Fault`], to check how the driver
+//! behaves when the bus misbehaves.
+//!
+//! The simulator is a cheap handle to shared state, so it can be cloned and passed to the driver
+//! while the test keeps a copy to inject voltages and inspect the registers.
+//!
+//! ```rust
+//! use ina3221::prelude::*;
+//! use ina3221::sim::SimulatedINA3221;
+//! use ina3221::{Address, Channel, INA3221};
+//!
+//! let sim = SimulatedINA3221::new(Address::Gnd);
+//! let mut ina = INA3221::new(sim.clone(), Address::Gnd);
+//!
+//! sim.set_shunt_voltage(Channel::Ch1, 10u32.milli_volts());
+//! assert_eq!(ina.get_shunt_voltage(Channel::Ch1).unwrap(), 10u32.milli_volts());
+//! ```
+
+use crate::config::{
+    BUS_MEASUREMENT_FLAG, CONTINUOUS_FLAG, DEFAULT_CONFIGURATION, MODE_MASK, RESET_FLAG,
+    SHUNT_MEASUREMENT_FLAG,
+};
+use crate::helpers;
+use crate::registers::Register;
+use crate::{Address, Channel, MaskEnableFlags};
+use hal::i2c::{ErrorKind, ErrorType, I2c, NoAcknowledgeSource, Operation};
+use ohms::Voltage;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::vec::Vec;
+
+/// Number of registers from Configuration (0x00) to the power valid lower limit (0x11)
+const REGISTER_COUNT: usize = 0x12;
+
+/// Register values after a power-on or reset
+const RESET_VALUES: [u16; REGISTER_COUNT] = [
+    DEFAULT_CONFIGURATION,
+    0x0000,
+    0x0000,
+    0x0000,
+    0x0000,
+    0x0000,
+    0x0000,
+    0x7FF8,
+    0x7FF8,
+    0x7FF8,
+    0x7FF8,
+    0x7FF8,
+    0x7FF8,
+    0x0000,
+    0x7FFE,
+    0x0002,
+    0x2710,
+    0x2328,
+];
+
+/// Largest positive values of the 13-bit and 15-bit signed registers
+const FULL_SCALE_13BIT: u16 = 0x7FF8;
+const FULL_SCALE_15BIT: u16 = 0x7FFE;
+
+const MANUFACTURER_ID: u16 = 0x5449;
+const DIE_ID: u16 = 0x3220;
+
+/// Represents a fault injected into a transaction with the simulated INA3221
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    /// The device does not acknowledge its address, so nothing is transferred
+    NackAddress,
+    /// The device acknowledges its address and the register pointer, but not the data
+    NackData,
+    /// Another controller wins arbitration, so nothing is transferred
+    ArbitrationLoss,
+    /// The data line is stuck, so every byte read has the given value and writes are lost
+    StuckBytes(u8),
+    /// The device resets before the transaction, restoring every register to its power-on value
+    Reset,
+}
+
+/// Simulated INA3221 that implements the blocking `I2c` trait
+///
+/// Transactions addressed to any other address are not acknowledged
+#[derive(Debug, Clone)]
+pub struct SimulatedINA3221 {
+    state: Rc<RefCell<State>>,
+}
+
+#[derive(Debug)]
+struct State {
+    address: u8,
+    pointer: u8,
+    registers: [u16; REGISTER_COUNT],
+    manufacturer_id: u16,
+    die_id: u16,
+    shunt_voltages: [i32; 3],
+    bus_voltages: [i32; 3],
+    transactions: usize,
+    faults: Vec<(usize, Fault)>,
+}
+
+impl SimulatedINA3221 {
+    /// Creates a simulated INA3221 in its power-on state, answering on a specific address
+    pub fn new(address: Address) -> SimulatedINA3221 {
+        let state = State {
+            address: address.bits(),
+            pointer: 0,
+            registers: RESET_VALUES,
+            manufacturer_id: MANUFACTURER_ID,
+            die_id: DIE_ID,
+            shunt_voltages: [0; 3],
+            bus_voltages: [0; 3],
+            transactions: 0,
+            faults: Vec::new(),
+        };
+
+        SimulatedINA3221 {
+            state: Rc::new(RefCell::new(state)),
+        }
+    }
+
+    /// Sets the shunt voltage across the inputs of a specific channel
+    ///
+    /// This is converted immediately in continuous mode, otherwise on the next triggered
+    /// measurement
+    pub fn set_shunt_voltage(&self, channel: Channel, voltage: Voltage) {
+        let mut state = self.state.borrow_mut();
+        state.shunt_voltages[channel.index()] = voltage.micro_volts();
+        state.convert_if_continuous();
+    }
+
+    /// Sets the bus voltage of a specific channel
+    ///
+    /// This is converted immediately in continuous mode, otherwise on the next triggered
+    /// measurement
+    pub fn set_bus_voltage(&self, channel: Channel, voltage: Voltage) {
+        let mut state = self.state.borrow_mut();
+        state.bus_voltages[channel.index()] = voltage.micro_volts();
+        state.convert_if_continuous();
+    }
+
+    /// Gets the value of a register without any side effects, such as clearing the alert flags
+    pub fn register(&self, register: u8) -> u16 {
+        self.state.borrow().peek(register)
+    }
+
+    /// Sets the value of a register, bypassing the read-only and reserved bits
+    pub fn set_register(&self, register: u8, value: u16) {
+        let mut state = self.state.borrow_mut();
+        if let Some(slot) = state.registers.get_mut(register as usize) {
+            *slot = value;
+        }
+    }
+
+    /// Gets the register pointer, which is set by the first byte of every write
+    pub fn pointer(&self) -> u8 {
+        self.state.borrow().pointer
+    }
+
+    /// Gets the number of I2C transactions addressed to the simulated INA3221
+    pub fn transaction_count(&self) -> usize {
+        self.state.borrow().transactions
+    }
+
+    /// Resets every register to its power-on value, as if the device was power cycled
+    pub fn power_cycle(&self) {
+        self.state.borrow_mut().power_cycle();
+    }
+
+    /// Injects a fault into the next transaction with the simulated INA3221
+    pub fn inject_fault(&self, fault: Fault) {
+        self.inject_fault_after(0, fault);
+    }
+
+    /// Injects a fault into a later transaction, after a number of transactions have completed
+    ///
+    /// Several faults can be injected to script a sequence of failures
+    pub fn inject_fault_after(&self, transactions: usize, fault: Fault) {
+        let mut state = self.state.borrow_mut();
+        let index = state.transactions + transactions;
+        state.faults.push((index, fault));
+    }
+
+    /// Removes all injected faults that have not happened yet
+    pub fn clear_faults(&self) {
+        self.state.borrow_mut().faults.clear();
+    }
+
+    /// Sets the manufacturer and die IDs reported by the simulated INA3221
+    pub fn set_identity(&self, manufacturer_id: u16, die_id: u16) {
+        let mut state = self.state.borrow_mut();
+        state.manufacturer_id = manufacturer_id;
+        state.die_id = die_id;
+    }
+
+    fn transaction(&self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), ErrorKind> {
+        let mut state = self.state.borrow_mut();
+        if address != state.address {
+            return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address));
+        }
+
+        let fault = state.take_fault();
+        state.transactions += 1;
+
+        match fault {
+            Some(Fault::NackAddress) => {
+                return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address))
+            }
+            Some(Fault::ArbitrationLoss) => return Err(ErrorKind::ArbitrationLoss),
+            Some(Fault::Reset) => state.power_cycle(),
+            _ => {}
+        }
+
+        for operation in operations {
+            match (fault, operation) {
+                (Some(Fault::NackData), Operation::Write(bytes)) => {
+                    if let Some(&pointer) = bytes.first() {
+                        state.pointer = pointer;
+                    }
+                    return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data));
+                }
+                (Some(Fault::NackData), Operation::Read(_)) => {
+                    return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data))
+                }
+                (Some(Fault::StuckBytes(_)), Operation::Write(_)) => {}
+                (Some(Fault::StuckBytes(value)), Operation::Read(buffer)) => buffer.fill(value),
+                (_, Operation::Write(bytes)) => state.write(bytes),
+                (_, Operation::Read(buffer)) => state.read(buffer),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl State {
+    fn take_fault(&mut self) -> Option<Fault> {
+        let transaction = self.transactions;
+        let index = self
+            .faults
+            .iter()
+            .position(|(index, _)| *index == transaction)?;
+        Some(self.faults.remove(index).1)
+    }
+
+    fn power_cycle(&mut self) {
+        self.pointer = 0;
+        self.registers = RESET_VALUES;
+    }
+
+    fn peek(&self, register: u8) -> u16 {
+        match register {
+            0xFE => self.manufacturer_id,
+            0xFF => self.die_id,
+            _ => self.registers.get(register as usize).copied().unwrap_or(0),
+        }
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        let Some((&pointer, value)) = bytes.split_first() else {
+            return;
+        };
+
+        self.pointer = pointer;
+        if let [msb, lsb, ..] = *value {
+            self.write_register(pointer, u16::from_be_bytes([msb, lsb]));
+        }
+    }
+
+    fn read(&mut self, buffer: &mut [u8]) {
+        // The pointer does not auto-increment, so longer reads repeat the same register
+        let bytes = self.peek(self.pointer).to_be_bytes();
+        for (byte, value) in buffer.iter_mut().zip(bytes.iter().cycle()) {
+            *byte = *value;
+        }
+
+        if self.pointer == Register::MaskEnable as u8 {
+            let cleared = MaskEnableFlags::ALERT_FLAGS | MaskEnableFlags::CONVERSION_READY;
+            self.registers[self.pointer as usize] &= !cleared.bits();
+        }
+    }
+
+    fn write_register(&mut self, register: u8, value: u16) {
+        let index = register as usize;
+        match register {
+            0x00 if value & RESET_FLAG > 0 => self.registers = RESET_VALUES,
+            0x00 => {
+                self.registers[index] = value;
+                self.registers[Register::MaskEnable as usize] &=
+                    !MaskEnableFlags::CONVERSION_READY.bits();
+
+                // Writing a measurement mode starts a conversion, even when triggered
+                if value & MODE_MASK & !CONTINUOUS_FLAG > 0 {
+                    self.convert();
+                }
+            }
+            0x07..=0x0C | 0x10 | 0x11 => self.registers[index] = value & 0xFFF8,
+            0x0E => self.registers[index] = value & 0xFFFE,
+            0x0F => {
+                // Only the latch and summation control bits are writable
+                let control = MaskEnableFlags::CONTROL_FLAGS.bits();
+                self.registers[index] = (self.registers[index] & !control) | (value & control);
+            }
+            _ => {}
+        }
+    }
+
+    fn convert_if_continuous(&mut self) {
+        let mode = self.registers[Register::Configuration as usize] & MODE_MASK;
+        if mode & CONTINUOUS_FLAG > 0 && mode & !CONTINUOUS_FLAG > 0 {
+            self.convert();
+        }
+    }
+
+    fn convert(&mut self) {
+        let config = self.registers[Register::Configuration as usize];
+        let mask_enable =
+            MaskEnableFlags::from_bits_truncate(self.registers[Register::MaskEnable as usize]);
+        let mut flags = mask_enable;
+        let mut sum = 0;
+        let mut power_valid = true;
+        let mut power_invalid = false;
+
+        let upper = self.decode(Register::PowerValidUpperLimit, helpers::decode_bus_voltage);
+        let lower = self.decode(Register::PowerValidLowerLimit, helpers::decode_bus_voltage);
+
+        for channel in Channel::ALL {
+            if config & channel.enable_flag() == 0 {
+                continue;
+            }
+
+            let index = channel.index();
+            if config & SHUNT_MEASUREMENT_FLAG > 0 {
+                self.registers[channel.shunt_voltage_register() as usize] = encode_clamped(
+                    self.shunt_voltages[index],
+                    helpers::encode_shunt_voltage,
+                    FULL_SCALE_13BIT,
+                );
+            }
+            if config & BUS_MEASUREMENT_FLAG > 0 {
+                self.registers[channel.bus_voltage_register() as usize] = encode_clamped(
+                    self.bus_voltages[index],
+                    helpers::encode_bus_voltage,
+                    FULL_SCALE_13BIT,
+                );
+            }
+
+            let shunt = self.decode(
+                channel.shunt_voltage_register(),
+                helpers::decode_shunt_voltage,
+            );
+            let bus = self.decode(channel.bus_voltage_register(), helpers::decode_bus_voltage);
+
+            let critical = self.decode(
+                channel.critical_alert_limit_register(),
+                helpers::decode_shunt_voltage,
+            );
+            let warning = self.decode(
+                channel.warning_alert_limit_register(),
+                helpers::decode_shunt_voltage,
+            );
+            flags.set(channel.critical_alert_flag(), shunt > critical);
+            flags.set(channel.warning_alert_flag(), shunt > warning);
+
+            if mask_enable.contains(channel.summation_control_flag()) {
+                sum += shunt;
+            }
+
+            power_valid &= bus >= upper;
+            power_invalid |= bus < lower;
+        }
+
+        self.registers[Register::ShuntVoltageSum as usize] =
+            encode_clamped(sum, helpers::encode_shunt_voltage_sum, FULL_SCALE_15BIT);
+        let sum_limit = self.decode(
+            Register::ShuntVoltageSumLimit,
+            helpers::decode_shunt_voltage_sum,
+        );
+        flags.set(MaskEnableFlags::SUMMATION_ALERT, sum > sum_limit);
+
+        // The power valid flag has hysteresis between the lower and upper limits
+        if power_valid {
+            flags.insert(MaskEnableFlags::POWER_VALID_ALERT);
+        } else if power_invalid {
+            flags.remove(MaskEnableFlags::POWER_VALID_ALERT);
+        }
+
+        // Latched alerts stay raised until the Mask/Enable register is read
+        if mask_enable.contains(MaskEnableFlags::CRITICAL_ALERT_LATCH) {
+            flags |= mask_enable & AlertFlags::CRITICAL;
+        }
+        if mask_enable.contains(MaskEnableFlags::WARNING_ALERT_LATCH) {
+            flags |= mask_enable & AlertFlags::WARNING;
+        }
+
+        flags.insert(MaskEnableFlags::CONVERSION_READY);
+        self.registers[Register::MaskEnable as usize] = flags.bits();
+    }
+
+    fn decode(&self, register: Register, decode: fn(u16) -> i32) -> i32 {
+        decode(self.registers[register as usize])
+    }
+}
+
+struct AlertFlags;
+
+impl AlertFlags {
+    const CRITICAL: MaskEnableFlags = MaskEnableFlags::from_bits_truncate(
+        MaskEnableFlags::CRITICAL_ALERT_1.bits()
+            | MaskEnableFlags::CRITICAL_ALERT_2.bits()
+            | MaskEnableFlags::CRITICAL_ALERT_3.bits()
+            | MaskEnableFlags::SUMMATION_ALERT.bits(),
+    );
+    const WARNING: MaskEnableFlags = MaskEnableFlags::from_bits_truncate(
+        MaskEnableFlags::WARNING_ALERT_1.bits()
+            | MaskEnableFlags::WARNING_ALERT_2.bits()
+            | MaskEnableFlags::WARNING_ALERT_3.bits(),
+    );
+}
+
+fn encode_clamped(micro_volts: i32, encode: fn(i32) -> Option<u16>, full_scale: u16) -> u16 {
+    // The ADC saturates at full-scale, so clamp to the largest value the register can hold
+    match encode(micro_volts) {
+        Some(value) => value,
+        None if micro_volts < 0 => 0x8000,
+        None => full_scale,
+    }
+}
+
+impl ErrorType for SimulatedINA3221 {
+    type Error = ErrorKind;
+}
+
+impl I2c for SimulatedINA3221 {
+    fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.transaction(address, &mut [Operation::Read(buffer)])
+    }
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.transaction(address, &mut [Operation::Write(bytes)])
+    }
+
+    fn write_iter<B>(&mut self, address: u8, bytes: B) -> Result<(), Self::Error>
+    where
+        B: IntoIterator<Item = u8>,
+    {
+        let bytes: Vec<u8> = bytes.into_iter().collect();
+        self.write(address, &bytes)
+    }
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.transaction(
+            address,
+            &mut [Operation::Write(bytes), Operation::Read(buffer)],
+        )
+    }
+
+    fn write_iter_read<B>(
+        &mut self,
+        address: u8,
+        bytes: B,
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error>
+    where
+        B: IntoIterator<Item = u8>,
+    {
+        let bytes: Vec<u8> = bytes.into_iter().collect();
+        self.write_read(address, &bytes, buffer)
+    }
+
+    fn transaction<'a>(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'a>],
+    ) -> Result<(), Self::Error> {
+        SimulatedINA3221::transaction(self, address, operations)
+    }
+
+    fn transaction_iter<'a, O>(&mut self, address: u8, operations: O) -> Result<(), Self::Error>
+    where
+        O: IntoIterator<Item = Operation<'a>>,
+    {
+        let mut operations: Vec<Operation<'a>> = operations.into_iter().collect();
+        SimulatedINA3221::transaction(self, address, &mut operations)
+    }
+}
@@ -0,0 +1,49 @@
+use crate::{Channel, Power};
+use ohms::{Current, Voltage};
+
+/// Represents the measurements of a single monitoring channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChannelReading {
+    /// Shunt voltage of the channel
+    pub shunt_voltage: Voltage,
+    /// Bus voltage of the channel
+    pub bus_voltage: Voltage,
+    /// Current draw of the channel, if a shunt resistance is configured
+    pub current: Option<Current>,
+    /// Power draw of the channel, if a shunt resistance is configured
+    pub power: Option<Power>,
+}
+
+impl ChannelReading {
+    /// Gets the load voltage of the channel, which is the sum of the bus and shunt voltages
+    pub fn load_voltage(&self) -> Voltage {
+        self.bus_voltage + self.shunt_voltage
+    }
+}
+
+/// Represents the measurements of all enabled monitoring channels taken together
+///
+/// Disabled channels are skipped when the snapshot is taken and have no reading
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Snapshot {
+    readings: [Option<ChannelReading>; 3],
+}
+
+impl Snapshot {
+    pub(crate) fn new(readings: [Option<ChannelReading>; 3]) -> Snapshot {
+        Snapshot { readings }
+    }
+
+    /// Gets the reading of a specific monitoring channel, if it was enabled
+    pub fn get(&self, channel: Channel) -> Option<&ChannelReading> {
+        self.readings[channel.index()].as_ref()
+    }
+
+    /// Iterates over the readings of all enabled monitoring channels
+    pub fn iter(&self) -> impl Iterator<Item = (Channel, &ChannelReading)> {
+        Channel::ALL
+            .into_iter()
+            .zip(self.readings.iter())
+            .filter_map(|(channel, reading)| reading.as_ref().map(|reading| (channel, reading)))
+    }
+}
@@ -0,0 +1,274 @@
+use core::future::{ready, Future, Ready};
+use core::pin::{pin, Pin};
+use core::task::{Context, Poll, Waker};
+use embedded_hal::i2c::{ErrorKind, ErrorType, I2c as _, Operation};
+use embedded_hal_async::delay::DelayUs;
+use embedded_hal_async::digital::{ErrorType as PinErrorType, Wait};
+use embedded_hal_async::i2c::I2c;
+use ina3221::asynch::INA3221;
+use ina3221::prelude::*;
+use ina3221::sim::SimulatedINA3221;
+
+/// Async adapter for the simulated INA3221, completing every transaction immediately
+struct AsyncSim(SimulatedINA3221);
+
+impl ErrorType for AsyncSim {
+    type Error = ErrorKind;
+}
+
+impl I2c for AsyncSim {
+    type ReadFuture<'a> = Ready<Result<(), ErrorKind>>;
+
+    fn read<'a>(&'a mut self, address: u8, read: &'a mut [u8]) -> Self::ReadFuture<'a> {
+        ready(self.0.read(address, read))
+    }
+
+    type WriteFuture<'a> = Ready<Result<(), ErrorKind>>;
+
+    fn write<'a>(&'a mut self, address: u8, write: &'a [u8]) -> Self::WriteFuture<'a> {
+        ready(self.0.write(address, write))
+    }
+
+    type WriteReadFuture<'a> = Ready<Result<(), ErrorKind>>;
+
+    fn write_read<'a>(
+        &'a mut self,
+        address: u8,
+        write: &'a [u8],
+        read: &'a mut [u8],
+    ) -> Self::WriteReadFuture<'a> {
+        ready(self.0.write_read(address, write, read))
+    }
+
+    type TransactionFuture<'a, 'b>
+        = Ready<Result<(), ErrorKind>>
+    where
+        'b: 'a;
+
+    fn transaction<'a, 'b>(
+        &'a mut self,
+        address: u8,
+        operations: &'a mut [Operation<'b>],
+    ) -> Self::TransactionFuture<'a, 'b> {
+        ready(self.0.transaction(address, operations))
+    }
+}
+
+struct NoDelay;
+
+impl DelayUs for NoDelay {
+    type Error = core::convert::Infallible;
+
+    type DelayUsFuture<'a> = Ready<Result<(), Self::Error>>;
+
+    fn delay_us(&mut self, _us: u32) -> Self::DelayUsFuture<'_> {
+        ready(Ok(()))
+    }
+
+    type DelayMsFuture<'a> = Ready<Result<(), Self::Error>>;
+
+    fn delay_ms(&mut self, _ms: u32) -> Self::DelayMsFuture<'_> {
+        ready(Ok(()))
+    }
+}
+
+/// Alert pin that is asserted from the start or after a number of falling edges
+struct MockPin {
+    low: bool,
+    falling_edges: usize,
+}
+
+/// Completes straight away if the pin event happened, otherwise never completes
+struct PinEvent(bool);
+
+impl Future for PinEvent {
+    type Output = Result<(), core::convert::Infallible>;
+
+    fn poll(self: Pin<&mut Self>, _context: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.0 {
+            true => Poll::Ready(Ok(())),
+            false => Poll::Pending,
+        }
+    }
+}
+
+impl PinErrorType for MockPin {
+    type Error = core::convert::Infallible;
+}
+
+impl Wait for MockPin {
+    type WaitForHighFuture<'a> = PinEvent;
+
+    fn wait_for_high(&mut self) -> Self::WaitForHighFuture<'_> {
+        PinEvent(!self.low)
+    }
+
+    type WaitForLowFuture<'a> = PinEvent;
+
+    fn wait_for_low(&mut self) -> Self::WaitForLowFuture<'_> {
+        PinEvent(self.low)
+    }
+
+    type WaitForRisingEdgeFuture<'a> = PinEvent;
+
+    fn wait_for_rising_edge(&mut self) -> Self::WaitForRisingEdgeFuture<'_> {
+        PinEvent(false)
+    }
+
+    type WaitForFallingEdgeFuture<'a> = PinEvent;
+
+    fn wait_for_falling_edge(&mut self) -> Self::WaitForFallingEdgeFuture<'_> {
+        let edge = self.falling_edges > 0;
+        if edge {
+            self.falling_edges -= 1;
+            self.low = true;
+        }
+        PinEvent(edge)
+    }
+
+    type WaitForAnyEdgeFuture<'a> = PinEvent;
+
+    fn wait_for_any_edge(&mut self) -> Self::WaitForAnyEdgeFuture<'_> {
+        self.wait_for_falling_edge()
+    }
+}
+
+/// Polls a future once, returning `None` if it is still waiting
+fn poll_once<F: Future>(future: F) -> Option<F::Output> {
+    let mut future = pin!(future);
+    let mut context = Context::from_waker(Waker::noop());
+    match future.as_mut().poll(&mut context) {
+        Poll::Ready(output) => Some(output),
+        Poll::Pending => None,
+    }
+}
+
+/// Polls a future to completion, which only works for futures that never wait on a waker
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut context = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+}
+
+fn setup() -> (SimulatedINA3221, INA3221<AsyncSim>) {
+    let sim = SimulatedINA3221::new(Address::Gnd);
+    let ina = INA3221::new(AsyncSim(sim.clone()), Address::Gnd);
+    (sim, ina)
+}
+
+#[test]
+fn probe_accepts_genuine_device() {
+    let sim = SimulatedINA3221::new(Address::Vs);
+    let mut ina = block_on(INA3221::probe(AsyncSim(sim), Address::Vs)).unwrap();
+
+    assert_eq!(ina.address(), Address::Vs);
+    assert_eq!(block_on(ina.get_die_id()).unwrap(), 0x3220);
+}
+
+#[test]
+fn reads_and_writes_the_configuration() {
+    let (sim, mut ina) = setup();
+
+    block_on(ina.set_mode(OperatingMode::PowerDown)).unwrap();
+    assert_eq!(sim.register(0x00), 0x7120);
+    assert_eq!(block_on(ina.get_mode()).unwrap(), OperatingMode::PowerDown);
+}
+
+#[test]
+fn reads_all_measurements() {
+    let (sim, mut ina) = setup();
+
+    ina.set_shunt_resistance(Channel::Ch2, 100u32.milli_ohms());
+    sim.set_shunt_voltage(Channel::Ch2, 10u32.milli_volts());
+    sim.set_bus_voltage(Channel::Ch2, 5_000u32.milli_volts());
+
+    let snapshot = block_on(ina.read_all()).unwrap();
+    let reading = snapshot.get(Channel::Ch2).unwrap();
+    assert_eq!(reading.shunt_voltage, 10u32.milli_volts());
+    assert_eq!(reading.bus_voltage, 5_000u32.milli_volts());
+    assert!(reading.current.is_some());
+}
+
+#[test]
+fn measure_once_triggers_a_single_conversion() {
+    let (sim, mut ina) = setup();
+
+    block_on(ina.set_mode(OperatingMode::PowerDown)).unwrap();
+    sim.set_bus_voltage(Channel::Ch1, 3_200u32.milli_volts());
+
+    let snapshot = block_on(ina.measure_once(&mut NoDelay)).unwrap();
+    assert_eq!(
+        snapshot.get(Channel::Ch1).unwrap().bus_voltage,
+        3_200u32.milli_volts()
+    );
+    assert_eq!(
+        block_on(ina.get_mode()).unwrap(),
+        OperatingMode::Triggered(Measurement::ShuntAndBus)
+    );
+}
+
+/// Raises the critical and warning alerts of channel 1 with a shunt voltage over both limits
+fn raise_alerts(sim: &SimulatedINA3221, ina: &mut INA3221<AsyncSim>) {
+    block_on(ina.set_critical_alert_limit(Channel::Ch1, 20u32.milli_volts())).unwrap();
+    block_on(ina.set_warning_alert_limit(Channel::Ch1, 10u32.milli_volts())).unwrap();
+    sim.set_shunt_voltage(Channel::Ch1, 30u32.milli_volts());
+}
+
+#[test]
+fn alert_waits_for_falling_edge() {
+    let (sim, mut ina) = setup();
+    raise_alerts(&sim, &mut ina);
+
+    // A pin that is already asserted does not report the alert again
+    let mut pin = MockPin {
+        low: true,
+        falling_edges: 0,
+    };
+    assert!(poll_once(ina.wait_for_critical_alert(&mut pin)).is_none());
+
+    pin.falling_edges = 1;
+    let event = poll_once(ina.wait_for_critical_alert(&mut pin))
+        .unwrap()
+        .unwrap();
+    assert_eq!(event.line(), AlertLine::Critical);
+    assert_eq!(event.channels().collect::<Vec<_>>(), [Channel::Ch1]);
+}
+
+#[test]
+fn alert_level_is_checked_when_enabled() {
+    let (sim, mut ina) = setup();
+    raise_alerts(&sim, &mut ina);
+
+    ina.set_alert_level_checked(true);
+    let mut pin = MockPin {
+        low: true,
+        falling_edges: 0,
+    };
+    let event = poll_once(ina.wait_for_critical_alert(&mut pin))
+        .unwrap()
+        .unwrap();
+    assert_eq!(event.channels().collect::<Vec<_>>(), [Channel::Ch1]);
+
+    pin.low = false;
+    assert!(poll_once(ina.wait_for_critical_alert(&mut pin)).is_none());
+}
+
+#[test]
+fn alert_only_acknowledges_awaited_line() {
+    let (sim, mut ina) = setup();
+    raise_alerts(&sim, &mut ina);
+
+    let mut pin = MockPin {
+        low: false,
+        falling_edges: 1,
+    };
+    block_on(ina.wait_for_critical_alert(&mut pin)).unwrap();
+
+    let flags = block_on(ina.read_alert_flags(false)).unwrap();
+    assert!(flags.contains(MaskEnableFlags::WARNING_ALERT_1));
+    assert!(!flags.contains(MaskEnableFlags::CRITICAL_ALERT_1));
+}
@@ -0,0 +1,727 @@
+use embedded_hal::delay::DelayUs;
+use embedded_hal::i2c::{ErrorKind, I2c, NoAcknowledgeSource};
+use ina3221::prelude::*;
+use ina3221::sim::SimulatedINA3221;
+use ina3221::{scan, Address, Channel, Current, Error, Voltage, INA3221};
+
+struct NoDelay;
+
+impl DelayUs for NoDelay {
+    type Error = core::convert::Infallible;
+
+    fn delay_us(&mut self, _us: u32) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+fn setup() -> (SimulatedINA3221, INA3221<SimulatedINA3221>) {
+    let sim = SimulatedINA3221::new(Address::Gnd);
+    let ina = INA3221::new(sim.clone(), Address::Gnd);
+    (sim, ina)
+}
+
+fn micro_volts(micro_volts: i32) -> Voltage {
+    Voltage::from_micro_volts(micro_volts)
+}
+
+#[test]
+fn probe_accepts_genuine_device() {
+    let sim = SimulatedINA3221::new(Address::Vs);
+    let ina = INA3221::probe(sim, Address::Vs).unwrap();
+
+    assert_eq!(ina.address(), Address::Vs);
+    assert_eq!(ina.get_manufacturer_id().unwrap(), 0x5449);
+    assert_eq!(ina.get_die_id().unwrap(), 0x3220);
+}
+
+#[test]
+fn probe_rejects_other_device() {
+    let sim = SimulatedINA3221::new(Address::Gnd);
+    sim.set_identity(0x5449, 0x2260);
+
+    let result = INA3221::probe(sim, Address::Gnd);
+    assert_eq!(
+        result.err(),
+        Some(Error::UnexpectedDevice {
+            manufacturer_id: 0x5449,
+            die_id: 0x2260,
+        })
+    );
+}
+
+#[test]
+fn probe_and_reset_restores_default_configuration() {
+    let sim = SimulatedINA3221::new(Address::Gnd);
+    sim.set_register(0x00, 0x0000);
+
+    let ina = INA3221::probe_and_reset(sim, Address::Gnd).unwrap();
+    assert_eq!(ina.read_config().unwrap(), Configuration::default());
+}
+
+#[test]
+fn scan_finds_only_genuine_devices() {
+    let mut sim = SimulatedINA3221::new(Address::Sda);
+
+    let devices = scan(&mut sim).unwrap();
+    assert_eq!(devices.len(), 1);
+    assert!(devices.contains(Address::Sda));
+    assert_eq!(devices.iter().collect::<Vec<_>>(), vec![Address::Sda]);
+
+    sim.set_identity(0x5449, 0x2260);
+    assert!(scan(&mut sim).unwrap().is_empty());
+}
+
+#[test]
+fn read_config_matches_power_on_default() {
+    let (_, ina) = setup();
+
+    let config = ina.read_config().unwrap();
+    assert_eq!(config, Configuration::default());
+    assert_eq!(config.bits(), 0x7127);
+    assert_eq!(
+        ina.get_mode().unwrap(),
+        OperatingMode::Continuous(Measurement::ShuntAndBus)
+    );
+    assert_eq!(ina.get_averaging().unwrap(), Averaging::Samples1);
+    assert_eq!(
+        ina.get_bus_conversion_time().unwrap(),
+        ConversionTime::Micros1100
+    );
+    assert_eq!(
+        ina.get_shunt_conversion_time().unwrap(),
+        ConversionTime::Micros1100
+    );
+}
+
+#[test]
+fn write_config_round_trips() {
+    let (sim, mut ina) = setup();
+
+    let config = Configuration::builder()
+        .channel_enabled(Channel::Ch2, false)
+        .averaging(Averaging::Samples64)
+        .bus_conversion_time(ConversionTime::Micros204)
+        .shunt_conversion_time(ConversionTime::Micros8244)
+        .mode(OperatingMode::Continuous(Measurement::Shunt))
+        .build();
+    ina.write_config(&config).unwrap();
+
+    assert_eq!(sim.register(0x00), config.bits());
+    assert_eq!(ina.read_config().unwrap(), config);
+}
+
+#[test]
+fn setters_only_change_their_own_field() {
+    let (sim, mut ina) = setup();
+
+    ina.set_mode(OperatingMode::PowerDown).unwrap();
+    assert_eq!(sim.register(0x00), 0x7120);
+
+    ina.set_averaging(Averaging::Samples1024).unwrap();
+    assert_eq!(sim.register(0x00), 0x7F20);
+
+    ina.set_channel_enabled(Channel::Ch1, false).unwrap();
+    assert_eq!(sim.register(0x00), 0x3F20);
+    assert!(!ina.is_channel_enabled(Channel::Ch1).unwrap());
+
+    ina.set_channels_enabled(&[true, false, true]).unwrap();
+    assert_eq!(sim.register(0x00), 0x5F20);
+
+    let mut statuses = [false; 3];
+    ina.get_channels_enabled(&mut statuses).unwrap();
+    assert_eq!(statuses, [true, false, true]);
+}
+
+#[test]
+fn channels_enabled_rejects_short_slices() {
+    let (sim, mut ina) = setup();
+
+    assert_eq!(
+        ina.set_channels_enabled(&[true, false]),
+        Err(Error::InvalidChannel)
+    );
+    assert_eq!(
+        ina.get_channels_enabled(&mut [false; 2]),
+        Err(Error::InvalidChannel)
+    );
+    assert_eq!(sim.register(0x00), 0x7127);
+}
+
+#[test]
+fn voltages_are_decoded_with_sign() {
+    let (sim, ina) = setup();
+
+    sim.set_shunt_voltage(Channel::Ch1, micro_volts(12_040));
+    sim.set_shunt_voltage(Channel::Ch2, micro_volts(-12_040));
+    sim.set_bus_voltage(Channel::Ch1, micro_volts(5_000_000));
+
+    assert_eq!(
+        ina.get_shunt_voltage(Channel::Ch1).unwrap(),
+        micro_volts(12_040)
+    );
+    assert_eq!(
+        ina.get_shunt_voltage(Channel::Ch2).unwrap(),
+        micro_volts(-12_040)
+    );
+    assert_eq!(
+        ina.get_bus_voltage(Channel::Ch1).unwrap(),
+        micro_volts(5_000_000)
+    );
+    assert_eq!(
+        ina.get_load_voltage(Channel::Ch1).unwrap(),
+        micro_volts(5_012_040)
+    );
+}
+
+#[test]
+fn measurements_saturate_at_full_scale() {
+    let (sim, ina) = setup();
+
+    sim.set_shunt_voltage(Channel::Ch1, micro_volts(200_000));
+    sim.set_shunt_voltage(Channel::Ch2, micro_volts(-200_000));
+
+    assert_eq!(
+        ina.get_shunt_voltage(Channel::Ch1).unwrap(),
+        micro_volts(163_800)
+    );
+    assert_eq!(
+        ina.get_shunt_voltage(Channel::Ch2).unwrap(),
+        micro_volts(-163_840)
+    );
+}
+
+#[test]
+fn current_and_power_use_shunt_resistance() {
+    let (sim, mut ina) = setup();
+
+    sim.set_shunt_voltage(Channel::Ch1, micro_volts(10_000));
+    sim.set_bus_voltage(Channel::Ch1, micro_volts(4_992_000));
+
+    assert_eq!(
+        ina.get_current(Channel::Ch1),
+        Err(Error::MissingShuntResistance)
+    );
+    assert_eq!(
+        ina.get_power(Channel::Ch1),
+        Err(Error::MissingShuntResistance)
+    );
+
+    ina.set_shunt_resistance(Channel::Ch1, 100u32.milli_ohms());
+    assert_eq!(
+        ina.get_current(Channel::Ch1).unwrap(),
+        Current::from_micro_amps(100_000)
+    );
+    assert_eq!(
+        ina.get_power(Channel::Ch1).unwrap(),
+        Power::from_micro_watts(500_200)
+    );
+}
+
+#[test]
+fn read_all_skips_disabled_channels() {
+    let (sim, mut ina) = setup();
+
+    ina.set_channel_enabled(Channel::Ch2, false).unwrap();
+    ina.set_shunt_resistance(Channel::Ch1, 100u32.milli_ohms());
+    sim.set_shunt_voltage(Channel::Ch1, micro_volts(10_000));
+    sim.set_bus_voltage(Channel::Ch3, micro_volts(3_304_000));
+
+    let snapshot = ina.read_all().unwrap();
+    let channels: Vec<_> = snapshot.iter().map(|(channel, _)| channel).collect();
+    assert_eq!(channels, vec![Channel::Ch1, Channel::Ch3]);
+
+    let reading = snapshot.get(Channel::Ch1).unwrap();
+    assert_eq!(reading.current, Some(Current::from_micro_amps(100_000)));
+
+    let reading = snapshot.get(Channel::Ch3).unwrap();
+    assert_eq!(reading.bus_voltage, micro_volts(3_304_000));
+    assert_eq!(reading.current, None);
+}
+
+#[test]
+fn read_all_if_ready_waits_for_conversion() {
+    let (sim, mut ina) = setup();
+
+    ina.set_mode(OperatingMode::PowerDown).unwrap();
+    assert!(ina.read_all_if_ready().unwrap().is_none());
+
+    sim.set_bus_voltage(Channel::Ch1, micro_volts(1_200_000));
+    ina.set_mode(OperatingMode::Continuous(Measurement::ShuntAndBus))
+        .unwrap();
+
+    let snapshot = ina.read_all_if_ready().unwrap().unwrap();
+    assert_eq!(
+        snapshot.get(Channel::Ch1).unwrap().bus_voltage,
+        micro_volts(1_200_000)
+    );
+}
+
+#[test]
+fn measure_once_triggers_a_single_conversion() {
+    let (sim, mut ina) = setup();
+
+    ina.set_mode(OperatingMode::PowerDown).unwrap();
+    sim.set_bus_voltage(Channel::Ch2, micro_volts(12_000_000));
+    assert_eq!(ina.get_bus_voltage(Channel::Ch2).unwrap(), micro_volts(0));
+
+    let snapshot = ina.measure_once(&mut NoDelay).unwrap();
+    assert_eq!(
+        snapshot.get(Channel::Ch2).unwrap().bus_voltage,
+        micro_volts(12_000_000)
+    );
+    assert_eq!(
+        ina.get_mode().unwrap(),
+        OperatingMode::Triggered(Measurement::ShuntAndBus)
+    );
+}
+
+#[test]
+fn measure_once_requires_an_enabled_channel() {
+    let (sim, mut ina) = setup();
+
+    ina.set_mode(OperatingMode::PowerDown).unwrap();
+    ina.set_channels_enabled(&[false, false, false]).unwrap();
+    let config = sim.register(0x00);
+
+    assert_eq!(
+        ina.measure_once(&mut NoDelay).err(),
+        Some(Error::NoChannelsEnabled)
+    );
+    assert_eq!(sim.register(0x00), config);
+}
+
+#[test]
+fn register_reads_use_a_single_transaction() {
+    let (sim, ina) = setup();
+
+    assert_eq!(ina.get_die_id().unwrap(), 0x3220);
+    assert_eq!(sim.transaction_count(), 1);
+    assert_eq!(sim.pointer(), 0xFF);
+}
+
+#[test]
+fn long_reads_repeat_the_register() {
+    let (mut sim, _) = setup();
+    sim.set_shunt_voltage(Channel::Ch1, micro_volts(10_000));
+    sim.set_bus_voltage(Channel::Ch1, micro_volts(5_000_000));
+
+    // The register pointer does not auto-increment, so the bus voltage is never returned
+    let mut buffer = [0u8; 4];
+    sim.write_read(0x40, &[0x01], &mut buffer).unwrap();
+    assert_eq!(buffer, [0x07, 0xD0, 0x07, 0xD0]);
+}
+
+fn set_all_voltages(sim: &SimulatedINA3221) {
+    for (index, channel) in Channel::ALL.into_iter().enumerate() {
+        let index = index as i32 + 1;
+        sim.set_shunt_voltage(channel, micro_volts(index * 1_000));
+        sim.set_bus_voltage(channel, micro_volts(index * 1_000_000));
+    }
+}
+
+#[test]
+fn read_all_reads_each_register_without_burst() {
+    let (sim, mut ina) = setup();
+    set_all_voltages(&sim);
+    ina.set_channel_enabled(Channel::Ch2, false).unwrap();
+    assert!(!ina.is_burst_enabled());
+
+    let before = sim.transaction_count();
+    let snapshot = ina.read_all().unwrap();
+
+    // One transaction for the configuration, then one per register of the enabled channels
+    assert_eq!(sim.transaction_count(), before + 5);
+    assert_eq!(snapshot.iter().count(), 2);
+}
+
+#[test]
+fn burst_reads_all_measurements_in_one_transaction() {
+    let (sim, mut ina) = setup();
+    set_all_voltages(&sim);
+    ina.set_burst_enabled(true);
+
+    let before = sim.transaction_count();
+    let snapshot = ina.read_all().unwrap();
+    assert_eq!(sim.transaction_count(), before + 2);
+
+    for (index, channel) in Channel::ALL.into_iter().enumerate() {
+        let index = index as i32 + 1;
+        let reading = snapshot.get(channel).unwrap();
+        assert_eq!(reading.shunt_voltage, micro_volts(index * 1_000));
+        assert_eq!(reading.bus_voltage, micro_volts(index * 1_000_000));
+    }
+
+    // The pointer is set before every register, so it ends on the last one
+    assert_eq!(sim.pointer(), 0x06);
+}
+
+#[test]
+fn burst_skips_disabled_channels() {
+    let (sim, mut ina) = setup();
+    set_all_voltages(&sim);
+    ina.set_burst_enabled(true);
+    ina.set_channel_enabled(Channel::Ch2, false).unwrap();
+
+    // Every measurement register is read, but only the enabled channels are returned
+    let before = sim.transaction_count();
+    let snapshot = ina.read_all().unwrap();
+    assert_eq!(sim.transaction_count(), before + 2);
+
+    let channels: Vec<_> = snapshot.iter().map(|(channel, _)| channel).collect();
+    assert_eq!(channels, vec![Channel::Ch1, Channel::Ch3]);
+}
+
+#[test]
+fn burst_is_skipped_without_enabled_channels() {
+    let (sim, mut ina) = setup();
+    ina.set_burst_enabled(true);
+    ina.set_channels_enabled(&[false, false, false]).unwrap();
+
+    let before = sim.transaction_count();
+    let snapshot = ina.read_all().unwrap();
+    assert_eq!(sim.transaction_count(), before + 1);
+    assert_eq!(snapshot.iter().count(), 0);
+}
+
+#[test]
+fn alert_limits_round_trip() {
+    let (sim, mut ina) = setup();
+
+    ina.set_critical_alert_limit(Channel::Ch1, micro_volts(100_000))
+        .unwrap();
+    ina.set_warning_alert_limit(Channel::Ch3, micro_volts(-40))
+        .unwrap();
+    assert_eq!(sim.register(0x07), 0x4E20);
+    assert_eq!(sim.register(0x0C), 0xFFF8);
+
+    assert_eq!(
+        ina.get_critical_alert_limit(Channel::Ch1).unwrap(),
+        micro_volts(100_000)
+    );
+    assert_eq!(
+        ina.get_warning_alert_limit(Channel::Ch3).unwrap(),
+        micro_volts(-40)
+    );
+
+    ina.set_shunt_resistance(Channel::Ch2, 100u32.milli_ohms());
+    ina.set_critical_current_limit(Channel::Ch2, 1u32.amps())
+        .unwrap();
+    assert_eq!(
+        ina.get_critical_alert_limit(Channel::Ch2).unwrap(),
+        micro_volts(100_000)
+    );
+    assert_eq!(
+        ina.get_critical_current_limit(Channel::Ch2).unwrap(),
+        1u32.amps()
+    );
+}
+
+#[test]
+fn alert_limits_reject_out_of_range() {
+    let (sim, mut ina) = setup();
+
+    assert_eq!(
+        ina.set_critical_alert_limit(Channel::Ch1, micro_volts(163_840)),
+        Err(Error::LimitOutOfRange)
+    );
+    assert_eq!(
+        ina.set_summation_alert_limit(micro_volts(655_360)),
+        Err(Error::LimitOutOfRange)
+    );
+    assert_eq!(
+        ina.set_power_valid_limits(micro_volts(0), micro_volts(32_768_000)),
+        Err(Error::LimitOutOfRange)
+    );
+    assert_eq!(sim.register(0x07), 0x7FF8);
+    assert_eq!(sim.register(0x0E), 0x7FFE);
+    assert_eq!(sim.register(0x10), 0x2710);
+}
+
+#[test]
+fn power_valid_limits_round_trip() {
+    let (sim, mut ina) = setup();
+
+    assert_eq!(
+        ina.get_power_valid_limits().unwrap(),
+        (micro_volts(9_000_000), micro_volts(10_000_000))
+    );
+
+    ina.set_power_valid_limits(micro_volts(3_000_000), micro_volts(3_200_000))
+        .unwrap();
+    assert_eq!(sim.register(0x11), 0x0BB8);
+    assert_eq!(sim.register(0x10), 0x0C80);
+}
+
+#[test]
+fn critical_alert_is_raised_and_acknowledged() {
+    let (sim, mut ina) = setup();
+
+    ina.set_critical_alert_limit(Channel::Ch2, micro_volts(50_000))
+        .unwrap();
+    sim.set_shunt_voltage(Channel::Ch2, micro_volts(60_000));
+
+    let status = ina.read_alert_status().unwrap();
+    assert!(status.is_critical(Channel::Ch2));
+    assert!(!status.is_warning(Channel::Ch2));
+    assert_eq!(
+        status.iter().collect::<Vec<_>>(),
+        vec![(Channel::Ch2, AlertKind::Critical)]
+    );
+
+    // Reading cleared the flag on the device, but the driver keeps it until acknowledged
+    assert_eq!(sim.register(0x0F) & 0x0100, 0);
+    assert!(ina.read_alert_status().unwrap().is_critical(Channel::Ch2));
+
+    assert!(ina.acknowledge_alerts().unwrap().is_critical(Channel::Ch2));
+    assert!(!ina.read_alert_status().unwrap().has_alerts());
+}
+
+#[test]
+fn read_alert_flags_preserves_alerts() {
+    let (sim, mut ina) = setup();
+
+    ina.set_warning_alert_limit(Channel::Ch1, micro_volts(1_000))
+        .unwrap();
+    sim.set_shunt_voltage(Channel::Ch1, micro_volts(2_000));
+
+    let flags = ina.read_alert_flags(true).unwrap();
+    assert!(flags.contains(MaskEnableFlags::WARNING_ALERT_1));
+
+    let flags = ina.read_alert_flags(false).unwrap();
+    assert!(flags.contains(MaskEnableFlags::WARNING_ALERT_1));
+
+    let flags = ina.read_alert_flags(false).unwrap();
+    assert!(!flags.contains(MaskEnableFlags::WARNING_ALERT_1));
+}
+
+#[test]
+fn alert_control_keeps_pending_alerts() {
+    let (sim, mut ina) = setup();
+
+    ina.set_critical_alert_limit(Channel::Ch3, micro_volts(1_000))
+        .unwrap();
+    sim.set_shunt_voltage(Channel::Ch3, micro_volts(2_000));
+
+    ina.set_critical_alert_latch(true).unwrap();
+    ina.set_summation_channel_enabled(Channel::Ch1, true)
+        .unwrap();
+    assert_eq!(sim.register(0x0F) & 0x7C00, 0x1400);
+
+    let control = ina.get_alert_control().unwrap();
+    assert_eq!(
+        control,
+        MaskEnableFlags::CRITICAL_ALERT_LATCH | MaskEnableFlags::SUMMATION_CONTROL_1
+    );
+    assert!(ina.is_summation_channel_enabled(Channel::Ch1).unwrap());
+
+    assert!(ina.acknowledge_alerts().unwrap().is_critical(Channel::Ch3));
+}
+
+#[test]
+fn summation_alert_uses_selected_channels() {
+    let (sim, mut ina) = setup();
+
+    ina.set_summation_channel_enabled(Channel::Ch1, true)
+        .unwrap();
+    ina.set_summation_channel_enabled(Channel::Ch2, true)
+        .unwrap();
+    ina.set_summation_alert_limit(micro_volts(100_000)).unwrap();
+
+    sim.set_shunt_voltage(Channel::Ch1, micro_volts(60_000));
+    sim.set_shunt_voltage(Channel::Ch2, micro_volts(60_000));
+    sim.set_shunt_voltage(Channel::Ch3, micro_volts(60_000));
+
+    assert_eq!(ina.get_shunt_voltage_sum().unwrap(), micro_volts(120_000));
+    assert_eq!(
+        ina.get_summation_alert_limit().unwrap(),
+        micro_volts(100_000)
+    );
+    assert!(ina.read_alert_status().unwrap().is_summation());
+}
+
+#[test]
+fn power_valid_flag_follows_bus_voltages() {
+    let (sim, mut ina) = setup();
+
+    ina.set_power_valid_limits(micro_volts(4_000_000), micro_volts(4_500_000))
+        .unwrap();
+    for channel in Channel::ALL {
+        sim.set_bus_voltage(channel, micro_volts(5_000_000));
+    }
+    assert!(ina.read_alert_status().unwrap().is_power_valid());
+
+    sim.set_bus_voltage(Channel::Ch2, micro_volts(3_000_000));
+    assert!(!ina.read_alert_status().unwrap().is_power_valid());
+}
+
+#[test]
+fn reset_restores_power_on_values() {
+    let (sim, mut ina) = setup();
+
+    ina.set_mode(OperatingMode::PowerDown).unwrap();
+    ina.set_critical_alert_limit(Channel::Ch1, micro_volts(1_000))
+        .unwrap();
+    ina.set_warning_alert_latch(true).unwrap();
+
+    ina.reset().unwrap();
+    assert_eq!(sim.register(0x00), 0x7127);
+    assert_eq!(sim.register(0x07), 0x7FF8);
+    assert_eq!(sim.register(0x0F), 0x0002);
+    assert_eq!(ina.get_alert_control().unwrap(), MaskEnableFlags::empty());
+}
+
+#[test]
+fn reset_config_clears_alert_control() {
+    let (sim, mut ina) = setup();
+
+    ina.set_critical_alert_latch(true).unwrap();
+    ina.write_config(&Configuration::builder().reset(true).build())
+        .unwrap();
+
+    ina.set_warning_alert_latch(true).unwrap();
+    assert_eq!(sim.register(0x0F) & 0x7C00, 0x0800);
+}
+
+#[test]
+fn invalidate_discards_alert_control() {
+    let (sim, mut ina) = setup();
+
+    ina.set_critical_alert_latch(true).unwrap();
+    sim.power_cycle();
+    ina.invalidate();
+
+    ina.set_warning_alert_latch(true).unwrap();
+    assert_eq!(sim.register(0x0F) & 0x7C00, 0x0800);
+}
+
+#[test]
+fn read_only_registers_ignore_writes() {
+    let mut sim = SimulatedINA3221::new(Address::Gnd);
+    sim.set_shunt_voltage(Channel::Ch1, micro_volts(400));
+
+    sim.write(0x40, &[0x01, 0x12, 0x34]).unwrap();
+    sim.write(0x40, &[0xFE, 0x12, 0x34]).unwrap();
+    sim.write(0x40, &[0x0F, 0xFF, 0xFF]).unwrap();
+
+    assert_eq!(sim.register(0x01), 0x0050);
+    assert_eq!(sim.register(0xFE), 0x5449);
+    // Only the control bits are writable, the conversion ready and timing control flags are kept
+    assert_eq!(sim.register(0x0F), 0x7C03);
+    assert_eq!(sim.pointer(), 0x0F);
+}
+
+#[test]
+fn other_addresses_are_not_acknowledged() {
+    let sim = SimulatedINA3221::new(Address::Gnd);
+    let ina = INA3221::new(sim, Address::Scl);
+
+    assert_eq!(
+        ina.get_configuration(),
+        Err(Error::I2c(ErrorKind::NoAcknowledge(
+            NoAcknowledgeSource::Address
+        )))
+    );
+}
+
+#[test]
+fn register_cache_skips_reads() {
+    let (sim, mut ina) = setup();
+
+    ina.set_cache_enabled(true);
+    ina.sync().unwrap();
+    let synced = sim.transaction_count();
+
+    ina.set_mode(OperatingMode::PowerDown).unwrap();
+    ina.set_averaging(Averaging::Samples4).unwrap();
+    ina.set_warning_alert_latch(true).unwrap();
+    assert_eq!(sim.transaction_count(), synced + 3);
+
+    assert_eq!(ina.get_mode().unwrap(), OperatingMode::PowerDown);
+    assert_eq!(ina.get_averaging().unwrap(), Averaging::Samples4);
+    assert_eq!(sim.transaction_count(), synced + 3);
+    assert_eq!(sim.register(0x00), 0x7320);
+}
+
+#[test]
+fn register_cache_resynchronises_after_external_change() {
+    let (sim, mut ina) = setup();
+
+    ina.set_cache_enabled(true);
+    ina.set_mode(OperatingMode::PowerDown).unwrap();
+
+    sim.power_cycle();
+    assert_eq!(ina.get_mode().unwrap(), OperatingMode::PowerDown);
+
+    ina.invalidate();
+    assert_eq!(
+        ina.get_mode().unwrap(),
+        OperatingMode::Continuous(Measurement::ShuntAndBus)
+    );
+}
+
+#[test]
+fn destroy_releases_the_bus() {
+    let (sim, ina) = setup();
+
+    let released = ina.destroy();
+    released.set_register(0x00, 0x0000);
+    assert_eq!(sim.register(0x00), 0x0000);
+}
+
+/// Logs every channel of any power monitor, as application code would
+fn log_channels<M: PowerMonitor>(monitor: &mut M) -> Result<Vec<(Voltage, Power)>, M::Error> {
+    (0..monitor.channel_count())
+        .map(|channel| Ok((monitor.bus_voltage(channel)?, monitor.power(channel)?)))
+        .collect()
+}
+
+#[test]
+fn power_monitor_reads_every_channel() {
+    let (sim, mut ina) = setup();
+    for channel in Channel::ALL {
+        ina.set_shunt_resistance(channel, 100u32.milli_ohms());
+    }
+    sim.set_shunt_voltage(Channel::Ch2, micro_volts(10_000));
+    sim.set_bus_voltage(Channel::Ch2, micro_volts(5_000_000));
+
+    assert_eq!(ina.channel_count(), 3);
+    assert_eq!(ina.manufacturer_id().unwrap(), 0x5449);
+    assert_eq!(ina.die_id().unwrap(), 0x3220);
+    assert_eq!(ina.shunt_voltage(1).unwrap(), micro_volts(10_000));
+    assert_eq!(
+        ina.current(1).unwrap(),
+        ina.get_current(Channel::Ch2).unwrap()
+    );
+
+    let readings = log_channels(&mut ina).unwrap();
+    assert_eq!(readings.len(), 3);
+    assert_eq!(readings[1].0, micro_volts(5_000_000));
+    assert_eq!(readings[1].1, ina.get_power(Channel::Ch2).unwrap());
+}
+
+#[test]
+fn power_monitor_current_limit_uses_critical_alert() {
+    let (sim, mut ina) = setup();
+    ina.set_shunt_resistance(Channel::Ch3, 100u32.milli_ohms());
+
+    ina.set_current_limit(2, 1u32.amps()).unwrap();
+    assert_eq!(ina.current_limit(2).unwrap(), 1u32.amps());
+    assert_eq!(
+        ina.get_critical_alert_limit(Channel::Ch3).unwrap(),
+        100u32.milli_volts()
+    );
+    assert_eq!(sim.register(0x0B), 0x4E20);
+}
+
+#[test]
+fn power_monitor_rejects_invalid_channels() {
+    let (_, mut ina) = setup();
+
+    assert_eq!(ina.bus_voltage(3), Err(Error::InvalidChannel));
+    assert_eq!(ina.shunt_voltage(256), Err(Error::InvalidChannel));
+    assert_eq!(
+        ina.set_current_limit(usize::MAX, 1u32.amps()),
+        Err(Error::InvalidChannel)
+    );
+    assert_eq!(ina.current(0), Err(Error::MissingShuntResistance));
+}
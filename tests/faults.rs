@@ -0,0 +1,419 @@
+use embedded_hal::delay::DelayUs;
+use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
+use ina3221::prelude::*;
+use ina3221::sim::{Fault, SimulatedINA3221};
+use ina3221::{scan, Address, Channel, Error, Voltage, INA3221};
+
+type Driver = INA3221<SimulatedINA3221>;
+type MethodResult = Result<(), Error<ErrorKind>>;
+type Method = fn(&mut Driver) -> MethodResult;
+
+struct NoDelay;
+
+impl DelayUs for NoDelay {
+    type Error = core::convert::Infallible;
+
+    fn delay_us(&mut self, _us: u32) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+struct BrokenDelay;
+
+impl DelayUs for BrokenDelay {
+    type Error = ();
+
+    fn delay_us(&mut self, _us: u32) -> Result<(), Self::Error> {
+        Err(())
+    }
+}
+
+fn setup() -> (SimulatedINA3221, Driver) {
+    let sim = SimulatedINA3221::new(Address::Gnd);
+    let mut ina = INA3221::new(sim.clone(), Address::Gnd);
+    for channel in Channel::ALL {
+        ina.set_shunt_resistance(channel, 100u32.milli_ohms());
+    }
+    (sim, ina)
+}
+
+fn micro_volts(micro_volts: i32) -> Voltage {
+    Voltage::from_micro_volts(micro_volts)
+}
+
+/// Every public method of the driver that accesses the I2C bus
+fn methods() -> Vec<(&'static str, Method)> {
+    vec![
+        ("get_configuration", |ina| ina.get_configuration().map(drop)),
+        ("read_config", |ina| ina.read_config().map(drop)),
+        ("write_config", |ina| {
+            ina.write_config(&Configuration::default())
+        }),
+        ("get_mode", |ina| ina.get_mode().map(drop)),
+        ("set_mode", |ina| ina.set_mode(OperatingMode::PowerDown)),
+        ("get_averaging", |ina| ina.get_averaging().map(drop)),
+        ("set_averaging", |ina| {
+            ina.set_averaging(Averaging::Samples4)
+        }),
+        ("get_bus_conversion_time", |ina| {
+            ina.get_bus_conversion_time().map(drop)
+        }),
+        ("set_bus_conversion_time", |ina| {
+            ina.set_bus_conversion_time(ConversionTime::Micros140)
+        }),
+        ("get_shunt_conversion_time", |ina| {
+            ina.get_shunt_conversion_time().map(drop)
+        }),
+        ("set_shunt_conversion_time", |ina| {
+            ina.set_shunt_conversion_time(ConversionTime::Micros140)
+        }),
+        ("get_channels_enabled", |ina| {
+            ina.get_channels_enabled(&mut [false; 3])
+        }),
+        ("set_channels_enabled", |ina| {
+            ina.set_channels_enabled(&[true, false, true])
+        }),
+        ("is_channel_enabled", |ina| {
+            ina.is_channel_enabled(Channel::Ch1).map(drop)
+        }),
+        ("set_channel_enabled", |ina| {
+            ina.set_channel_enabled(Channel::Ch1, false)
+        }),
+        ("get_shunt_voltage", |ina| {
+            ina.get_shunt_voltage(Channel::Ch1).map(drop)
+        }),
+        ("get_bus_voltage", |ina| {
+            ina.get_bus_voltage(Channel::Ch1).map(drop)
+        }),
+        ("get_load_voltage", |ina| {
+            ina.get_load_voltage(Channel::Ch1).map(drop)
+        }),
+        ("get_current", |ina| ina.get_current(Channel::Ch1).map(drop)),
+        ("get_power", |ina| ina.get_power(Channel::Ch1).map(drop)),
+        ("read_all", |ina| ina.read_all().map(drop)),
+        ("read_all (burst)", |ina| {
+            ina.set_burst_enabled(true);
+            ina.read_all().map(drop)
+        }),
+        ("read_all_if_ready", |ina| ina.read_all_if_ready().map(drop)),
+        ("measure_once", |ina| {
+            ina.measure_once(&mut NoDelay).map(drop)
+        }),
+        ("get_critical_alert_limit", |ina| {
+            ina.get_critical_alert_limit(Channel::Ch2).map(drop)
+        }),
+        ("set_critical_alert_limit", |ina| {
+            ina.set_critical_alert_limit(Channel::Ch2, micro_volts(1_000))
+        }),
+        ("get_critical_current_limit", |ina| {
+            ina.get_critical_current_limit(Channel::Ch2).map(drop)
+        }),
+        ("set_critical_current_limit", |ina| {
+            ina.set_critical_current_limit(Channel::Ch2, 1u32.amps())
+        }),
+        ("set_critical_alert_latch", |ina| {
+            ina.set_critical_alert_latch(true)
+        }),
+        ("get_warning_alert_limit", |ina| {
+            ina.get_warning_alert_limit(Channel::Ch3).map(drop)
+        }),
+        ("set_warning_alert_limit", |ina| {
+            ina.set_warning_alert_limit(Channel::Ch3, micro_volts(1_000))
+        }),
+        ("get_warning_current_limit", |ina| {
+            ina.get_warning_current_limit(Channel::Ch3).map(drop)
+        }),
+        ("set_warning_current_limit", |ina| {
+            ina.set_warning_current_limit(Channel::Ch3, 1u32.amps())
+        }),
+        ("set_warning_alert_latch", |ina| {
+            ina.set_warning_alert_latch(true)
+        }),
+        ("is_summation_channel_enabled", |ina| {
+            ina.is_summation_channel_enabled(Channel::Ch1).map(drop)
+        }),
+        ("set_summation_channel_enabled", |ina| {
+            ina.set_summation_channel_enabled(Channel::Ch1, true)
+        }),
+        ("get_shunt_voltage_sum", |ina| {
+            ina.get_shunt_voltage_sum().map(drop)
+        }),
+        ("get_summation_alert_limit", |ina| {
+            ina.get_summation_alert_limit().map(drop)
+        }),
+        ("set_summation_alert_limit", |ina| {
+            ina.set_summation_alert_limit(micro_volts(1_000))
+        }),
+        ("get_power_valid_limits", |ina| {
+            ina.get_power_valid_limits().map(drop)
+        }),
+        ("set_power_valid_limits", |ina| {
+            ina.set_power_valid_limits(micro_volts(3_000_000), micro_volts(3_200_000))
+        }),
+        ("read_alert_flags", |ina| {
+            ina.read_alert_flags(false).map(drop)
+        }),
+        ("read_alert_status", |ina| ina.read_alert_status().map(drop)),
+        ("acknowledge_alerts", |ina| {
+            ina.acknowledge_alerts().map(drop)
+        }),
+        ("get_alert_control", |ina| ina.get_alert_control().map(drop)),
+        ("set_alert_control", |ina| {
+            ina.set_alert_control(MaskEnableFlags::WARNING_ALERT_LATCH)
+        }),
+        ("get_manufacturer_id", |ina| {
+            ina.get_manufacturer_id().map(drop)
+        }),
+        ("get_die_id", |ina| ina.get_die_id().map(drop)),
+        ("reset", |ina| ina.reset()),
+        ("sync", |ina| ina.sync()),
+    ]
+}
+
+/// Checks that a fault in any transaction of every method is reported as the expected I2C error
+fn assert_fault_reported(fault: Fault, expected: ErrorKind) {
+    for (name, method) in methods() {
+        let (sim, mut ina) = setup();
+        method(&mut ina).unwrap_or_else(|error| panic!("{} failed: {:?}", name, error));
+        let transactions = sim.transaction_count();
+        assert!(transactions > 0, "{} did not access the bus", name);
+
+        for transaction in 0..transactions {
+            let (sim, mut ina) = setup();
+            sim.inject_fault_after(transaction, fault);
+
+            assert_eq!(
+                method(&mut ina),
+                Err(Error::I2c(expected)),
+                "{} with {:?} in transaction {}",
+                name,
+                fault,
+                transaction
+            );
+        }
+    }
+}
+
+#[test]
+fn every_method_reports_address_nack() {
+    assert_fault_reported(
+        Fault::NackAddress,
+        ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address),
+    );
+}
+
+#[test]
+fn every_method_reports_data_nack() {
+    assert_fault_reported(
+        Fault::NackData,
+        ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data),
+    );
+}
+
+#[test]
+fn every_method_reports_arbitration_loss() {
+    assert_fault_reported(Fault::ArbitrationLoss, ErrorKind::ArbitrationLoss);
+}
+
+#[test]
+fn shunt_resistance_does_not_access_the_bus() {
+    let (sim, mut ina) = setup();
+    sim.inject_fault(Fault::NackAddress);
+
+    ina.set_shunt_resistance(Channel::Ch1, 50u32.milli_ohms());
+    assert_eq!(
+        ina.get_shunt_resistance(Channel::Ch1),
+        Some(50u32.milli_ohms())
+    );
+    assert_eq!(ina.address(), Address::Gnd);
+    assert_eq!(sim.transaction_count(), 0);
+}
+
+#[test]
+fn data_nack_leaves_register_unchanged() {
+    let (sim, mut ina) = setup();
+
+    // The Configuration register is read in the first transaction, then written in the second
+    sim.inject_fault_after(1, Fault::NackData);
+    assert!(ina.set_mode(OperatingMode::PowerDown).is_err());
+
+    assert_eq!(sim.register(0x00), 0x7127);
+    assert_eq!(sim.pointer(), 0x00);
+}
+
+#[test]
+fn stuck_bytes_are_returned_as_read() {
+    let (sim, ina) = setup();
+
+    sim.inject_fault(Fault::StuckBytes(0xFF));
+    assert_eq!(ina.get_configuration().unwrap(), 0xFFFF);
+
+    // A stuck line reads as the smallest negative shunt voltage
+    sim.inject_fault(Fault::StuckBytes(0xFF));
+    assert_eq!(
+        ina.get_shunt_voltage(Channel::Ch1).unwrap(),
+        micro_volts(-40)
+    );
+}
+
+#[test]
+fn stuck_bytes_lose_writes() {
+    let (sim, mut ina) = setup();
+
+    sim.inject_fault_after(1, Fault::StuckBytes(0xFF));
+    ina.set_mode(OperatingMode::PowerDown).unwrap();
+    assert_eq!(sim.register(0x00), 0x7127);
+}
+
+#[test]
+fn stuck_bytes_fail_identity_check() {
+    let sim = SimulatedINA3221::new(Address::Gnd);
+    for transaction in 0..2 {
+        sim.inject_fault_after(transaction, Fault::StuckBytes(0xFF));
+    }
+
+    assert_eq!(
+        INA3221::probe(sim, Address::Gnd).err(),
+        Some(Error::UnexpectedDevice {
+            manufacturer_id: 0xFFFF,
+            die_id: 0xFFFF,
+        })
+    );
+}
+
+#[test]
+fn measure_once_times_out_if_conversion_never_completes() {
+    let (sim, mut ina) = setup();
+
+    // Every read of the Mask/Enable register returns zero after the measurement is triggered
+    for transaction in 2..1_000 {
+        sim.inject_fault_after(transaction, Fault::StuckBytes(0x00));
+    }
+
+    assert_eq!(ina.measure_once(&mut NoDelay).err(), Some(Error::Timeout));
+}
+
+#[test]
+fn measure_once_reports_delay_errors() {
+    let (_sim, mut ina) = setup();
+
+    assert_eq!(ina.measure_once(&mut BrokenDelay).err(), Some(Error::Delay));
+}
+
+#[test]
+fn failed_burst_is_not_retried_register_by_register() {
+    let (sim, mut ina) = setup();
+    ina.set_burst_enabled(true);
+
+    // The Configuration register is read in the first transaction, then the burst in the second
+    sim.inject_fault_after(1, Fault::ArbitrationLoss);
+    assert_eq!(
+        ina.read_all().err(),
+        Some(Error::I2c(ErrorKind::ArbitrationLoss))
+    );
+    assert_eq!(sim.transaction_count(), 2);
+}
+
+#[test]
+fn reset_mid_session_restores_default_configuration() {
+    let (sim, mut ina) = setup();
+
+    ina.set_mode(OperatingMode::PowerDown).unwrap();
+    ina.set_critical_alert_limit(Channel::Ch1, micro_volts(1_000))
+        .unwrap();
+
+    sim.inject_fault(Fault::Reset);
+    assert_eq!(
+        ina.get_mode().unwrap(),
+        OperatingMode::Continuous(Measurement::ShuntAndBus)
+    );
+    assert_eq!(
+        ina.get_critical_alert_limit(Channel::Ch1).unwrap(),
+        micro_volts(163_800)
+    );
+}
+
+#[test]
+fn reset_mid_session_is_hidden_by_cache_until_sync() {
+    let (sim, mut ina) = setup();
+
+    ina.set_cache_enabled(true);
+    ina.set_mode(OperatingMode::PowerDown).unwrap();
+    ina.set_warning_alert_latch(true).unwrap();
+
+    sim.inject_fault(Fault::Reset);
+    ina.get_manufacturer_id().unwrap();
+    assert_eq!(ina.get_mode().unwrap(), OperatingMode::PowerDown);
+    assert_eq!(
+        ina.get_alert_control().unwrap(),
+        MaskEnableFlags::WARNING_ALERT_LATCH
+    );
+
+    ina.sync().unwrap();
+    assert_eq!(
+        ina.get_mode().unwrap(),
+        OperatingMode::Continuous(Measurement::ShuntAndBus)
+    );
+    assert_eq!(ina.get_alert_control().unwrap(), MaskEnableFlags::empty());
+}
+
+#[test]
+fn wrong_manufacturer_id_is_rejected() {
+    let mut sim = SimulatedINA3221::new(Address::Gnd);
+    sim.set_identity(0x4154, 0x3220);
+
+    assert!(scan(&mut sim).unwrap().is_empty());
+    assert_eq!(
+        INA3221::probe(sim.clone(), Address::Gnd).err(),
+        Some(Error::UnexpectedDevice {
+            manufacturer_id: 0x4154,
+            die_id: 0x3220,
+        })
+    );
+
+    // The unchecked constructor still binds to the device
+    let ina = INA3221::new(sim, Address::Gnd);
+    assert_eq!(ina.get_manufacturer_id().unwrap(), 0x4154);
+}
+
+#[test]
+fn scan_skips_nack_but_reports_other_errors() {
+    let mut sim = SimulatedINA3221::new(Address::Gnd);
+
+    sim.inject_fault(Fault::NackAddress);
+    assert!(scan(&mut sim).unwrap().is_empty());
+
+    sim.inject_fault(Fault::ArbitrationLoss);
+    assert_eq!(
+        scan(&mut sim).err(),
+        Some(Error::I2c(ErrorKind::ArbitrationLoss))
+    );
+
+    assert!(scan(&mut sim).unwrap().contains(Address::Gnd));
+}
+
+#[test]
+fn failed_acknowledge_keeps_pending_alerts() {
+    let (sim, mut ina) = setup();
+
+    ina.set_critical_alert_limit(Channel::Ch1, micro_volts(1_000))
+        .unwrap();
+    sim.set_shunt_voltage(Channel::Ch1, micro_volts(2_000));
+    assert!(ina.read_alert_status().unwrap().is_critical(Channel::Ch1));
+
+    sim.inject_fault(Fault::NackAddress);
+    assert!(ina.acknowledge_alerts().is_err());
+
+    assert!(ina.acknowledge_alerts().unwrap().is_critical(Channel::Ch1));
+}
+
+#[test]
+fn faults_can_be_cleared() {
+    let (sim, ina) = setup();
+
+    sim.inject_fault(Fault::NackAddress);
+    sim.inject_fault_after(1, Fault::ArbitrationLoss);
+    sim.clear_faults();
+
+    assert_eq!(ina.get_configuration().unwrap(), 0x7127);
+}
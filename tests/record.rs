@@ -0,0 +1,257 @@
+use core::cell::Cell;
+use core::fmt;
+use embedded_hal::i2c::{ErrorKind, I2c, NoAcknowledgeSource, Operation};
+use ina3221::prelude::*;
+use ina3221::record::{Recorder, Replay, ReplayError};
+use ina3221::sim::{Fault, SimulatedINA3221};
+use ina3221::{Address, Channel, Error, INA3221};
+
+/// Clock that advances by 100us every time it is read
+fn ticking_clock() -> impl FnMut() -> u64 {
+    let time = Cell::new(0);
+    move || {
+        time.set(time.get() + 100);
+        time.get()
+    }
+}
+
+/// Sink that only accepts a fixed number of bytes
+struct FixedSink {
+    text: String,
+    capacity: usize,
+}
+
+impl fmt::Write for FixedSink {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.text.len() + s.len() > self.capacity {
+            return Err(fmt::Error);
+        }
+        self.text.push_str(s);
+        Ok(())
+    }
+}
+
+fn record<F>(sim: &SimulatedINA3221, session: F) -> String
+where
+    F: FnOnce(&mut INA3221<Recorder<SimulatedINA3221, String, fn() -> u64>>),
+{
+    let recorder = Recorder::new(sim.clone(), String::new(), (|| 0) as fn() -> u64);
+    let mut ina = INA3221::new(recorder, Address::Gnd);
+    session(&mut ina);
+
+    let (_, log) = ina.destroy().destroy();
+    log
+}
+
+#[test]
+fn recorder_logs_register_reads_and_writes() {
+    let sim = SimulatedINA3221::new(Address::Gnd);
+    let recorder = Recorder::new(sim, String::new(), ticking_clock());
+    let mut ina = INA3221::new(recorder, Address::Gnd);
+
+    ina.set_mode(OperatingMode::PowerDown).unwrap();
+
+    let (_, log) = ina.destroy().destroy();
+    assert_eq!(log, "100 40 W00 R00=7127\n200 40 W00=7120\n");
+}
+
+#[test]
+fn recorder_logs_failed_transactions() {
+    let sim = SimulatedINA3221::new(Address::Gnd);
+    sim.inject_fault(Fault::NackData);
+
+    let log = record(&sim, |ina| {
+        assert!(ina.get_die_id().is_err());
+    });
+    assert_eq!(log, "0 40 WFF RFF=0000 !nack-data\n");
+}
+
+#[test]
+fn recorder_logs_unknown_register_pointer() {
+    let sim = SimulatedINA3221::new(Address::Gnd);
+    let mut recorder = Recorder::new(sim, String::new(), || 0);
+
+    let mut buffer = [0u8; 2];
+    recorder.read(0x40, &mut buffer).unwrap();
+    recorder.write(0x41, &[0xFE]).unwrap_err();
+    recorder.read(0x40, &mut buffer).unwrap();
+
+    assert_eq!(
+        recorder.log(),
+        "0 40 R??=7127\n0 41 WFE !nack-address\n0 40 R??=7127\n"
+    );
+}
+
+#[test]
+fn recorder_logs_combined_transactions() {
+    let sim = SimulatedINA3221::new(Address::Gnd);
+    let mut recorder = Recorder::new(sim, String::new(), || 0);
+
+    let mut buffer = [0u8; 2];
+    recorder.write_read(0x40, &[0xFE], &mut buffer).unwrap();
+    recorder
+        .write_iter(0x40, [0x07, 0x12, 0x38].iter().copied())
+        .unwrap();
+    recorder
+        .transaction(
+            0x40,
+            &mut [Operation::Write(&[0x07]), Operation::Read(&mut buffer)],
+        )
+        .unwrap();
+
+    assert_eq!(
+        recorder.log(),
+        "0 40 WFE RFE=5449\n0 40 W07=1238\n0 40 W07 R07=1238\n"
+    );
+}
+
+#[test]
+fn recorder_reports_truncated_log() {
+    let sim = SimulatedINA3221::new(Address::Gnd);
+    let sink = FixedSink {
+        text: String::new(),
+        capacity: 20,
+    };
+    let recorder = Recorder::new(sim, sink, || 0);
+    let ina = INA3221::new(recorder, Address::Gnd);
+
+    // The transactions still succeed once the sink is full
+    assert_eq!(ina.get_configuration().unwrap(), 0x7127);
+    assert_eq!(ina.get_die_id().unwrap(), 0x3220);
+
+    let recorder = ina.destroy();
+    assert!(recorder.is_truncated());
+    assert_eq!(recorder.log().text, "0 40 W00 R00=7127\n0 ");
+}
+
+#[test]
+fn replay_reproduces_recorded_session() {
+    let sim = SimulatedINA3221::new(Address::Gnd);
+    let mut recorded = None;
+    let log = record(&sim, |ina| {
+        ina.set_critical_alert_limit(Channel::Ch1, 1u32.milli_volts())
+            .unwrap();
+        sim.set_shunt_voltage(Channel::Ch1, 2u32.milli_volts());
+        sim.set_bus_voltage(Channel::Ch2, 5u32.volts());
+        recorded = Some((ina.read_all().unwrap(), ina.read_alert_status().unwrap()));
+    });
+
+    let mut replay = Replay::new(&log);
+    let mut ina = INA3221::new(&mut replay, Address::Gnd);
+    ina.set_critical_alert_limit(Channel::Ch1, 1u32.milli_volts())
+        .unwrap();
+    let replayed = (ina.read_all().unwrap(), ina.read_alert_status().unwrap());
+
+    assert_eq!(Some(replayed), recorded);
+    assert!(replayed.1.is_critical(Channel::Ch1));
+    assert!(replay.is_finished());
+}
+
+#[test]
+fn replay_reproduces_recorded_errors() {
+    let sim = SimulatedINA3221::new(Address::Gnd);
+    sim.inject_fault(Fault::NackAddress);
+    sim.inject_fault_after(2, Fault::ArbitrationLoss);
+
+    let log = record(&sim, |ina| {
+        assert!(ina.get_mode().is_err());
+        assert!(ina.get_mode().is_ok());
+        assert!(ina.get_mode().is_err());
+    });
+
+    let ina = INA3221::new(Replay::new(&log), Address::Gnd);
+    assert_eq!(
+        ina.get_mode(),
+        Err(Error::I2c(ReplayError::Bus(ErrorKind::NoAcknowledge(
+            NoAcknowledgeSource::Address
+        ))))
+    );
+    assert_eq!(
+        ina.get_mode(),
+        Ok(OperatingMode::Continuous(Measurement::ShuntAndBus))
+    );
+    assert_eq!(
+        ina.get_mode(),
+        Err(Error::I2c(ReplayError::Bus(ErrorKind::ArbitrationLoss)))
+    );
+    assert_eq!(ina.get_mode(), Err(Error::I2c(ReplayError::Finished)));
+}
+
+#[test]
+fn replay_ignores_comments_and_timestamps() {
+    let log = "\
+# Captured from a field unit
+
+5000000 40 WFE RFE=5449
+
+# Second read
+5000300 40 WFF RFF=3220
+";
+
+    let ina = INA3221::probe(Replay::new(log), Address::Gnd).unwrap();
+    assert!(ina.destroy().is_finished());
+}
+
+#[test]
+fn replay_detects_mismatched_transactions() {
+    let log = "0 40 W00 R00=7127\n";
+
+    // Different register
+    let ina = INA3221::new(Replay::new(log), Address::Gnd);
+    assert_eq!(
+        ina.get_die_id(),
+        Err(Error::I2c(ReplayError::Mismatch { line: 1 }))
+    );
+
+    // Different address
+    let ina = INA3221::new(Replay::new(log), Address::Vs);
+    assert_eq!(
+        ina.get_configuration(),
+        Err(Error::I2c(ReplayError::Mismatch { line: 1 }))
+    );
+
+    // More transactions than recorded
+    let mut ina = INA3221::new(Replay::new(log), Address::Gnd);
+    assert_eq!(ina.get_configuration(), Ok(0x7127));
+    assert_eq!(
+        ina.set_mode(OperatingMode::PowerDown),
+        Err(Error::I2c(ReplayError::Finished))
+    );
+}
+
+#[test]
+fn replay_detects_mismatched_lengths() {
+    let mut replay = Replay::new("0 40 R??=71\n0 40 W00=7127\n");
+
+    let mut buffer = [0u8; 2];
+    assert_eq!(
+        replay.read(0x40, &mut buffer),
+        Err(ReplayError::Mismatch { line: 1 })
+    );
+    assert_eq!(
+        replay.write(0x40, &[0x00, 0x71]),
+        Err(ReplayError::Mismatch { line: 2 })
+    );
+}
+
+#[test]
+fn replay_reports_malformed_lines() {
+    let log = "0 40 W00\n0 40 R00=????\nfoo\n0 40 W00 !unknown\n";
+    let mut replay = Replay::new(log);
+
+    let mut buffer = [0u8; 2];
+    assert_eq!(replay.write(0x40, &[0x00]), Ok(()));
+    assert_eq!(
+        replay.read(0x40, &mut buffer),
+        Err(ReplayError::Malformed { line: 2 })
+    );
+    assert_eq!(
+        replay.write(0x40, &[0x00]),
+        Err(ReplayError::Malformed { line: 3 })
+    );
+    assert_eq!(
+        replay.write(0x40, &[0x00]),
+        Err(ReplayError::Malformed { line: 4 })
+    );
+    assert!(replay.is_finished());
+}